@@ -0,0 +1,103 @@
+#!/usr/bin/env python3
+# rebuilds udp.pcap and udp.pcapng byte for byte: run from anywhere, the files land next to this script.
+#
+# both captures hold the same five frames, which pcap_fixtures in src/main.rs checks against:
+#   1. UDP over IPv4, 10.0.0.1:8001 -> 10.0.0.2:8002, "hello"
+#   2. TCP over IPv4, skipped by the reader
+#   3. UDP over IPv6, [fe80::1]:9000 -> [fe80::2]:9001, bytes 0..200
+#   4. UDP over IPv4 behind a VLAN tag, 1300 bytes of 7: more than PACKET_DATA_SIZE
+#   5. the first fragment of a UDP datagram over IPv4 (more fragments set), skipped by the reader
+#
+# udp.pcap is classic little-endian pcap (microseconds, Ethernet, snaplen 65535). udp.pcapng is
+# big-endian pcapng with an Ethernet interface (0) and a raw IP one (1): frames 2 and 3 go through
+# the raw IP interface, frame 4 is a Simple Packet Block and the rest are Enhanced Packet Blocks.
+
+import os
+import struct
+
+
+def checksum(data):
+    if len(data) % 2:
+        data += b"\0"
+    total = sum(struct.unpack("!%dH" % (len(data) // 2), data))
+    while total >> 16:
+        total = (total & 0xFFFF) + (total >> 16)
+    return ~total & 0xFFFF
+
+
+def ipv4(src, dst, proto, payload, frag=0):
+    header = struct.pack("!BBHHHBBH4s4s", 0x45, 0, 20 + len(payload), 1, frag, 64, proto, 0, bytes(src), bytes(dst))
+    header = header[:10] + struct.pack("!H", checksum(header)) + header[12:]
+    return header + payload
+
+
+def ipv6(src, dst, next_header, payload):
+    return struct.pack("!IHBB", 6 << 28, len(payload), next_header, 64) + bytes(src) + bytes(dst) + payload
+
+
+def udp(source_port, destination_port, data):
+    return struct.pack("!HHHH", source_port, destination_port, 8 + len(data), 0) + data
+
+
+def ethernet(ethertype, packet, vlan=None):
+    header = b"\x02\0\0\0\0\x02" + b"\x02\0\0\0\0\x01"
+    if vlan is not None:
+        header += struct.pack("!HH", 0x8100, vlan)
+    return header + struct.pack("!H", ethertype) + packet
+
+
+def pcap(frames):
+    out = struct.pack("<IHHiIII", 0xA1B2C3D4, 2, 4, 0, 0, 65535, 1)
+    for i, frame in enumerate(frames):
+        out += struct.pack("<IIII", 1700000000 + i, 0, len(frame), len(frame)) + frame
+    return out
+
+
+def block(kind, body):
+    body += b"\0" * (-len(body) % 4)
+    length = 12 + len(body)
+    return struct.pack(">II", kind, length) + body + struct.pack(">I", length)
+
+
+def enhanced_packet(interface, data):
+    return block(6, struct.pack(">IIIII", interface, 0, 0, len(data), len(data)) + data)
+
+
+def pcapng(frames, packets):
+    out = block(0x0A0D0D0A, struct.pack(">IHHq", 0x1A2B3C4D, 1, 0, -1))
+    out += block(1, struct.pack(">HHI", 1, 0, 65535))
+    out += block(1, struct.pack(">HHI", 101, 0, 65535))
+    out += enhanced_packet(0, frames[0])
+    out += enhanced_packet(1, packets[1])
+    out += enhanced_packet(1, packets[2])
+    out += block(3, struct.pack(">I", len(frames[3])) + frames[3])
+    out += enhanced_packet(0, frames[4])
+    return out
+
+
+def main():
+    a, b = [10, 0, 0, 1], [10, 0, 0, 2]
+    v6a, v6b = [0xFE, 0x80] + [0] * 13 + [1], [0xFE, 0x80] + [0] * 13 + [2]
+    packets = [
+        ipv4(a, b, 17, udp(8001, 8002, b"hello")),
+        ipv4(a, b, 6, b"\0" * 20),
+        ipv6(v6a, v6b, 17, udp(9000, 9001, bytes(range(200)))),
+        ipv4(b, a, 17, udp(8003, 8001, bytes([7]) * 1300)),
+        ipv4(a, b, 17, udp(8001, 8002, b"fragment"), frag=0x2000),
+    ]
+    frames = [
+        ethernet(0x0800, packets[0]),
+        ethernet(0x0800, packets[1]),
+        ethernet(0x86DD, packets[2]),
+        ethernet(0x0800, packets[3], vlan=5),
+        ethernet(0x0800, packets[4]),
+    ]
+    here = os.path.dirname(os.path.abspath(__file__))
+    with open(os.path.join(here, "udp.pcap"), "wb") as f:
+        f.write(pcap(frames))
+    with open(os.path.join(here, "udp.pcapng"), "wb") as f:
+        f.write(pcapng(frames, packets))
+
+
+if __name__ == "__main__":
+    main()
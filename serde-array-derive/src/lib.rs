@@ -0,0 +1,137 @@
+//! `#[fast_array_serde]`: puts `#[serde(with = "serde_bytes_array")]` on every `[u8; N]` field,
+//! so a forgotten attribute can't silently fall back to the slow tuple encoding.
+//!
+//! ```ignore
+//! #[fast_array_serde]
+//! #[derive(Serialize, Deserialize)]
+//! pub struct Packet {
+//!     buffer: [u8; PACKET_DATA_SIZE],
+//!     #[fast_array(skip)]
+//!     tag: [u8; 4],
+//!     flags: u64,
+//! }
+//! ```
+//!
+//! The adapter path defaults to `serde_bytes_array` resolved where the struct is defined, and can be
+//! changed with `#[fast_array_serde(with = "crate::some::adapter")]`. It must be placed above
+//! `#[derive(...)]` so serde sees the rewritten fields.
+
+use {
+    proc_macro::TokenStream,
+    proc_macro2::Span,
+    quote::quote,
+    syn::{
+        parse_macro_input, parse_quote, punctuated::Punctuated, Attribute, Data, DeriveInput, Error, Expr,
+        ExprLit, Field, Fields, Lit, LitStr, Meta, Token, Type,
+    },
+};
+
+const DEFAULT_ADAPTER: &str = "serde_bytes_array";
+
+#[proc_macro_attribute]
+pub fn fast_array_serde(args: TokenStream, input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(args with Punctuated::<Meta, Token![,]>::parse_terminated);
+    let mut input = parse_macro_input!(input as DeriveInput);
+
+    match adapter_path(&args).and_then(|adapter| rewrite(&mut input, &adapter)) {
+        Ok(()) => quote!(#input).into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
+fn adapter_path(args: &Punctuated<Meta, Token![,]>) -> syn::Result<LitStr> {
+    let mut adapter = LitStr::new(DEFAULT_ADAPTER, Span::call_site());
+    for arg in args {
+        match arg {
+            Meta::NameValue(name_value) if name_value.path.is_ident("with") => match &name_value.value {
+                Expr::Lit(ExprLit { lit: Lit::Str(path), .. }) => adapter = path.clone(),
+                value => return Err(Error::new_spanned(value, "expected a string literal path")),
+            },
+            arg => return Err(Error::new_spanned(arg, "expected `with = \"path::to::adapter\"`")),
+        }
+    }
+    Ok(adapter)
+}
+
+fn rewrite(input: &mut DeriveInput, adapter: &LitStr) -> syn::Result<()> {
+    match &mut input.data {
+        Data::Struct(data) => rewrite_fields(&mut data.fields, adapter),
+        Data::Enum(data) => data
+            .variants
+            .iter_mut()
+            .try_for_each(|variant| rewrite_fields(&mut variant.fields, adapter)),
+        Data::Union(data) => Err(Error::new_spanned(data.union_token, "unions are not supported")),
+    }
+}
+
+fn rewrite_fields(fields: &mut Fields, adapter: &LitStr) -> syn::Result<()> {
+    fields.iter_mut().try_for_each(|field| rewrite_field(field, adapter))
+}
+
+fn rewrite_field(field: &mut Field, adapter: &LitStr) -> syn::Result<()> {
+    let skip = take_skip_attribute(&mut field.attrs)?;
+    if skip || !is_byte_array(&field.ty) || has_custom_serde(&field.attrs) {
+        return Ok(());
+    }
+    field.attrs.push(parse_quote!(#[serde(with = #adapter)]));
+    Ok(())
+}
+
+// strips our `#[fast_array(skip)]` helper attribute: nothing else would accept it
+fn take_skip_attribute(attrs: &mut Vec<Attribute>) -> syn::Result<bool> {
+    let mut skip = false;
+    let mut error = None;
+    attrs.retain(|attr| {
+        if !attr.path().is_ident("fast_array") {
+            return true;
+        }
+        if let Err(e) = attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("skip") {
+                skip = true;
+                Ok(())
+            } else {
+                Err(meta.error("expected `skip`"))
+            }
+        }) {
+            error.get_or_insert(e);
+        }
+        false
+    });
+    match error {
+        Some(error) => Err(error),
+        None => Ok(skip),
+    }
+}
+
+fn is_byte_array(ty: &Type) -> bool {
+    match ty {
+        Type::Array(array) => matches!(&*array.elem, Type::Path(path) if path.qself.is_none() && path.path.is_ident("u8")),
+        Type::Group(group) => is_byte_array(&group.elem),
+        Type::Paren(paren) => is_byte_array(&paren.elem),
+        _ => false,
+    }
+}
+
+// leave fields alone that already pick their own encoding
+fn has_custom_serde(attrs: &[Attribute]) -> bool {
+    attrs.iter().any(|attr| {
+        if attr.path().is_ident("serde_as") {
+            return true;
+        }
+        if !attr.path().is_ident("serde") {
+            return false;
+        }
+        let mut custom = false;
+        let _ = attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("with") || meta.path.is_ident("serialize_with") || meta.path.is_ident("deserialize_with") {
+                custom = true;
+            }
+            // skip over `= value` so the remaining items still parse
+            if meta.input.peek(Token![=]) {
+                meta.value()?.parse::<Expr>()?;
+            }
+            Ok(())
+        });
+        custom
+    })
+}
@@ -2,209 +2,5834 @@
 
 extern crate test;
 
+#[cfg_attr(not(any(test, feature = "format-bincode")), allow(unused_imports))]
 use serde_derive::Serialize;
+#[cfg_attr(not(any(test, feature = "format-bincode")), allow(unused_imports))]
 use serde_derive::Deserialize;
 
 pub const PACKET_DATA_SIZE: usize = 1280 - 40 - 8;
 
-#[bench]
-fn bench_serialize_vanilla(bencher: &mut test::Bencher) {
-    #[derive(Default, Clone, Serialize)]
-    pub struct Packet {
-        buffer: [u8; 32],
-        flags: u64,
+// what travels next to `buffer` in a real packet: how much of it is used, where it came from,
+// and a few bits of state
+#[cfg(all(test, feature = "packet-meta"))]
+mod packet_meta {
+    use {
+        serde_derive::{Deserialize, Serialize},
+        std::net::{IpAddr, Ipv4Addr},
+    };
+
+    bitflags::bitflags! {
+        #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+        pub struct PacketFlags: u8 {
+            const DISCARD = 1 << 0;
+            const FORWARDED = 1 << 1;
+            const REPAIR = 1 << 2;
+            const SIMPLE_VOTE_TX = 1 << 3;
+            const TRACER_PACKET = 1 << 4;
+        }
     }
 
-    let mut output_binary = vec![];
-    let mut input_packets: Vec<_> = std::iter::repeat(Packet::default()).take(32).collect();
+    #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+    pub struct Meta {
+        pub size: usize,
+        pub addr: IpAddr,
+        pub port: u16,
+        pub flags: PacketFlags,
+    }
+    impl Default for Meta {
+        #[inline(always)]
+        fn default() -> Self {
+            Self {
+                size: 0,
+                addr: IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+                port: 0,
+                flags: PacketFlags::empty(),
+            }
+        }
+    }
 
-    bencher.iter(|| {
-        test::black_box(bincode::serialize_into(&mut output_binary, &input_packets).unwrap());
-        output_binary.clear();
-    })
+    impl Meta {
+        /// what generated packets carry, as opposed to ones read from a capture
+        pub fn synthetic(size: usize) -> Self {
+            Self {
+                size,
+                addr: crate::payload::SYNTHETIC_SOURCE.ip(),
+                port: crate::payload::SYNTHETIC_SOURCE.port(),
+                flags: PacketFlags::FORWARDED | PacketFlags::SIMPLE_VOTE_TX,
+            }
+        }
+    }
 }
 
-#[bench]
-fn bench_serialize_serde_as(bencher: &mut test::Bencher) {
-    use serde_with::serde_as;
+// shared `[u8; N]` adapter: raw bytes for binary formats like bincode, a hex string for
+// human-readable ones like JSON instead of an array of numbers.
+// deserialize accepts any of raw bytes, a number array, a hex string or a base64 string.
+#[cfg(feature = "strategy-serde-bytes")]
+#[cfg(test)]
+mod serde_bytes_array {
+    use {
+        core::{convert::TryInto, fmt},
+        serde::{
+            de::{Error, SeqAccess, Visitor},
+            Deserializer, Serializer,
+        },
+    };
 
-    #[serde_as]
-    #[derive(Default, Clone, Serialize)]
-    pub struct Packet {
-        #[serde_as(as = "[_; 32]")]
-        buffer: [u8; 32],
-        flags: u64,
+    #[inline(always)]
+    pub(crate) fn serialize<S>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&hex::encode(bytes))
+        } else {
+            serde_bytes::serialize(bytes, serializer)
+        }
     }
 
-    let mut output_binary = vec![];
-    let mut input_packets: Vec<_> = std::iter::repeat(Packet::default()).take(32).collect();
-
-    bencher.iter(|| {
-        test::black_box(bincode::serialize_into(&mut output_binary, &input_packets).unwrap());
-        output_binary.clear();
-    })
-}
-
-#[bench]
-fn bench_serialize_serde_arrays(bencher: &mut test::Bencher) {
-    #[derive(Default, Clone, Serialize)]
-    pub struct Packet {
-        #[serde(with = "serde_arrays")]
-        buffer: [u8; 32],
-        flags: u64,
+    #[inline(always)]
+    pub(crate) fn deserialize<'de, D, const N: usize>(deserializer: D) -> Result<[u8; N], D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(ArrayVisitor::<N>)
+        } else {
+            deserializer.deserialize_bytes(ArrayVisitor::<N>)
+        }
     }
 
-    let mut output_binary = vec![];
-    let mut input_packets: Vec<_> = std::iter::repeat(Packet::default()).take(32).collect();
-
-    bencher.iter(|| {
-        test::black_box(bincode::serialize_into(&mut output_binary, &input_packets).unwrap());
-        output_binary.clear();
-    })
-}
+    /// `deserialize()` straight into `place`; raw bytes are copied without an intermediate array
+    #[inline(always)]
+    pub(crate) fn deserialize_in_place<'de, D, const N: usize>(deserializer: D, place: &mut [u8; N]) -> Result<(), D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(InPlaceVisitor(place))
+        } else {
+            deserializer.deserialize_bytes(InPlaceVisitor(place))
+        }
+    }
 
-fn bench_serialize_serde_bytes(bencher: &mut test::Bencher) {
-    mod serde_bytes_array {
+    // same as the parent module, but emits base64 instead of hex for human-readable formats
+    #[cfg_attr(not(feature = "format-json"), allow(dead_code))]
+    pub(crate) mod base64 {
         use {
-            core::convert::TryInto,
-            serde::{de::Error, Deserializer, Serializer},
+            ::base64::{engine::general_purpose::STANDARD, Engine},
+            serde::{Deserializer, Serializer},
         };
 
+        #[inline(always)]
         pub(crate) fn serialize<S>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error>
         where
             S: Serializer,
         {
-            serde_bytes::serialize(bytes, serializer)
+            if serializer.is_human_readable() {
+                serializer.serialize_str(&STANDARD.encode(bytes))
+            } else {
+                serde_bytes::serialize(bytes, serializer)
+            }
         }
 
+        #[inline(always)]
         pub(crate) fn deserialize<'de, D, const N: usize>(deserializer: D) -> Result<[u8; N], D::Error>
         where
             D: Deserializer<'de>,
         {
-            let slice: &[u8] = serde_bytes::deserialize(deserializer)?;
-            let array: [u8; N] = slice.try_into().map_err(|_| {
-                let expected = format!("[u8; {}]", N);
-                D::Error::invalid_length(slice.len(), &expected.as_str())
-            })?;
-            Ok(array)
+            super::deserialize(deserializer)
         }
     }
 
-    #[derive(Default, Clone, Serialize)]
-    pub struct Packet {
-        #[serde(with = "serde_bytes_array")]
-        buffer: [u8; 32],
-        flags: u64,
+    struct ArrayVisitor<const N: usize>;
+
+    impl<'de, const N: usize> Visitor<'de> for ArrayVisitor<N> {
+        type Value = [u8; N];
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            write!(formatter, "[u8; {}] as bytes, a hex or base64 string, or a sequence", N)
+        }
+
+        #[inline(always)]
+        fn visit_bytes<E>(self, bytes: &[u8]) -> Result<Self::Value, E>
+        where
+            E: Error,
+        {
+            bytes.try_into().map_err(|_| E::invalid_length(bytes.len(), &self))
+        }
+
+        // hex is always 2 * N chars long. base64 of N bytes is too for N = 2 and N = 4 ("AQI=",
+        // "AQIDBA=="), so a string of that length is tried as hex first and falls back to base64.
+        // that can't mix them up because base64 of those sizes always ends in '=', which isn't hex
+        fn visit_str<E>(self, s: &str) -> Result<Self::Value, E>
+        where
+            E: Error,
+        {
+            use ::base64::{engine::general_purpose::STANDARD, Engine};
+
+            let mut array = [0; N];
+            if s.len() == 2 * N && hex::decode_to_slice(s, &mut array).is_ok() {
+                Ok(array)
+            } else {
+                let vec = STANDARD.decode(s).map_err(E::custom)?;
+                self.visit_bytes(&vec)
+            }
+        }
+
+        fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+        where
+            A: SeqAccess<'de>,
+        {
+            let mut array = [0; N];
+            for (i, byte) in array.iter_mut().enumerate() {
+                *byte = seq
+                    .next_element()?
+                    .ok_or_else(|| A::Error::invalid_length(i, &self))?;
+            }
+            if seq.next_element::<u8>()?.is_some() {
+                return Err(A::Error::invalid_length(N + 1, &self));
+            }
+            Ok(array)
+        }
     }
 
-    let mut output_binary = vec![];
-    let mut input_packets: Vec<_> = std::iter::repeat(Packet::default()).take(32).collect();
+    struct InPlaceVisitor<'a, const N: usize>(&'a mut [u8; N]);
 
-    bencher.iter(|| {
-        test::black_box(bincode::serialize_into(&mut output_binary, &input_packets).unwrap());
-        output_binary.clear();
-    })
-}
+    impl<'de, const N: usize> Visitor<'de> for InPlaceVisitor<'_, N> {
+        type Value = ();
 
-#[bench]
-fn bench_serialize_serde_arrays_normal(bencher: &mut test::Bencher) {
-    #[derive(Clone, Serialize)]
-    pub struct Packet {
-        #[serde(with = "serde_arrays")]
-        buffer: [u8; PACKET_DATA_SIZE],
-        flags: u64,
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            ArrayVisitor::<N>.expecting(formatter)
+        }
+
+        #[inline(always)]
+        fn visit_bytes<E>(self, bytes: &[u8]) -> Result<Self::Value, E>
+        where
+            E: Error,
+        {
+            if bytes.len() != N {
+                return Err(E::invalid_length(bytes.len(), &self));
+            }
+            self.0.copy_from_slice(bytes);
+            Ok(())
+        }
+
+        fn visit_str<E>(self, s: &str) -> Result<Self::Value, E>
+        where
+            E: Error,
+        {
+            *self.0 = ArrayVisitor::<N>.visit_str(s)?;
+            Ok(())
+        }
+
+        fn visit_seq<A>(self, seq: A) -> Result<Self::Value, A::Error>
+        where
+            A: SeqAccess<'de>,
+        {
+            *self.0 = ArrayVisitor::<N>.visit_seq(seq)?;
+            Ok(())
+        }
     }
-    impl Default for Packet {
+}
+
+// `[u8; N]` newtype that serializes like `#[serde(with = "serde_bytes_array")]` without the attribute
+#[cfg(feature = "strategy-serde-bytes")]
+#[cfg(test)]
+mod fixed_bytes {
+    use {
+        core::{
+            fmt,
+            ops::{Deref, DerefMut},
+            str::FromStr,
+        },
+        serde::{Deserialize, Deserializer, Serialize, Serializer},
+    };
+
+    #[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+    pub struct FixedBytes<const N: usize>(pub [u8; N]);
+
+    impl<const N: usize> Default for FixedBytes<N> {
+        #[inline(always)]
         fn default() -> Self {
-            Self {
-                buffer: [0; PACKET_DATA_SIZE],
-                flags: 3,
-            }
+            Self([0; N])
         }
     }
 
-    let mut output_binary = vec![];
-    let mut input_packets: Vec<_> = std::iter::repeat(Packet::default()).take(512).collect();
+    impl<const N: usize> Deref for FixedBytes<N> {
+        type Target = [u8; N];
 
-    bencher.iter(|| {
-        test::black_box(bincode::serialize_into(&mut output_binary, &input_packets).unwrap());
-        output_binary.clear();
-    })
-}
+        #[inline(always)]
+        fn deref(&self) -> &Self::Target {
+            &self.0
+        }
+    }
 
-#[bench]
-fn bench_serialize_serde_as_normal(bencher: &mut test::Bencher) {
-    use serde_with::serde_as;
+    impl<const N: usize> DerefMut for FixedBytes<N> {
+        #[inline(always)]
+        fn deref_mut(&mut self) -> &mut Self::Target {
+            &mut self.0
+        }
+    }
 
-    #[serde_as]
-    #[derive(Clone, Serialize)]
-    pub struct Packet {
-        #[serde_as(as = "[_; PACKET_DATA_SIZE]")]
-        buffer: [u8; PACKET_DATA_SIZE],
-        flags: u64,
+    impl<const N: usize> From<[u8; N]> for FixedBytes<N> {
+        #[inline(always)]
+        fn from(array: [u8; N]) -> Self {
+            Self(array)
+        }
     }
-    impl Default for Packet {
-        fn default() -> Self {
-            Self {
-                buffer: [0; PACKET_DATA_SIZE],
-                flags: 3,
+
+    impl<const N: usize> From<FixedBytes<N>> for [u8; N] {
+        #[inline(always)]
+        fn from(bytes: FixedBytes<N>) -> Self {
+            bytes.0
+        }
+    }
+
+    impl<const N: usize> AsRef<[u8]> for FixedBytes<N> {
+        #[inline(always)]
+        fn as_ref(&self) -> &[u8] {
+            &self.0
+        }
+    }
+
+    impl<const N: usize> fmt::Display for FixedBytes<N> {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            for byte in &self.0 {
+                write!(f, "{:02x}", byte)?;
             }
+            Ok(())
         }
     }
 
-    let mut output_binary = vec![];
-    let mut input_packets: Vec<_> = std::iter::repeat(Packet::default()).take(512).collect();
+    impl<const N: usize> fmt::Debug for FixedBytes<N> {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "FixedBytes<{}>({})", N, self)
+        }
+    }
 
-    bencher.iter(|| {
-        test::black_box(bincode::serialize_into(&mut output_binary, &input_packets).unwrap());
-        output_binary.clear();
-    })
+    impl<const N: usize> FromStr for FixedBytes<N> {
+        type Err = hex::FromHexError;
+
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            let mut array = [0; N];
+            hex::decode_to_slice(s, &mut array)?;
+            Ok(Self(array))
+        }
+    }
+
+    impl<const N: usize> Serialize for FixedBytes<N> {
+        #[inline(always)]
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            super::serde_bytes_array::serialize(&self.0, serializer)
+        }
+    }
+
+    impl<'de, const N: usize> Deserialize<'de> for FixedBytes<N> {
+        #[inline(always)]
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            super::serde_bytes_array::deserialize(deserializer).map(Self)
+        }
+    }
 }
 
-mod serde_as_bytes {
-    use super::*;
+// `serde_bytes_array` as a serde_with type, so it also applies inside `Option`, `Vec`, maps etc.:
+// `#[serde_as(as = "Option<FastBytes>")]`
+#[cfg(all(feature = "strategy-serde-with", feature = "strategy-serde-bytes"))]
+#[cfg(test)]
+mod fast_bytes {
+    use {
+        serde::{Deserializer, Serializer},
+        serde_with::{DeserializeAs, SerializeAs},
+    };
 
-    use serde_with::{serde_as, Bytes};
+    pub struct FastBytes;
 
-    #[serde_as]
-    #[derive(Clone, Serialize, Deserialize)]
-    pub struct Packet {
-        #[serde_as(as = "Bytes")]
-        buffer: [u8; PACKET_DATA_SIZE],
-        flags: u64,
+    impl<const N: usize> SerializeAs<[u8; N]> for FastBytes {
+        #[inline(always)]
+        fn serialize_as<S>(source: &[u8; N], serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            super::serde_bytes_array::serialize(source, serializer)
+        }
     }
-    impl Default for Packet {
-        fn default() -> Self {
-            Self {
-                buffer: [0; PACKET_DATA_SIZE],
-                flags: 3,
+
+    impl<'de, const N: usize> DeserializeAs<'de, [u8; N]> for FastBytes {
+        #[inline(always)]
+        fn deserialize_as<D>(deserializer: D) -> Result<[u8; N], D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            super::serde_bytes_array::deserialize(deserializer)
+        }
+    }
+}
+
+// `[T; N]` of primitive numbers as one little-endian byte string instead of N separate elements.
+// on little-endian targets that is a straight memcpy both ways.
+#[cfg(test)]
+mod serde_le_array {
+    use {
+        core::{fmt, marker::PhantomData, mem::size_of},
+        serde::{
+            de::{Error, SeqAccess, Visitor},
+            Deserializer, Serializer,
+        },
+    };
+
+    /// # Safety
+    /// implementors must have no padding and accept any bit pattern, and `to_le` must be
+    /// a byte swap on big-endian targets and a no-op on little-endian ones. being a swap,
+    /// it converts back from little-endian too.
+    pub unsafe trait LeBytes: Copy + Default {
+        fn to_le(self) -> Self;
+    }
+
+    macro_rules! impl_le_bytes {
+        ($($int:ty),*) => {
+            $(
+                unsafe impl LeBytes for $int {
+                    #[inline(always)]
+                    fn to_le(self) -> Self {
+                        <$int>::to_le(self)
+                    }
+                }
+            )*
+        };
+    }
+    impl_le_bytes!(u8, i8, u16, i16, u32, i32, u64, i64, u128, i128);
+
+    macro_rules! impl_le_bytes_float {
+        ($($float:ty),*) => {
+            $(
+                unsafe impl LeBytes for $float {
+                    #[inline(always)]
+                    fn to_le(self) -> Self {
+                        <$float>::from_bits(self.to_bits().to_le())
+                    }
+                }
+            )*
+        };
+    }
+    impl_le_bytes_float!(f32, f64);
+
+    #[inline(always)]
+    fn as_bytes<T: LeBytes>(slice: &[T]) -> &[u8] {
+        // SAFETY: LeBytes types have no padding, so every byte is initialized
+        unsafe { core::slice::from_raw_parts(slice.as_ptr() as *const u8, core::mem::size_of_val(slice)) }
+    }
+
+    #[inline(always)]
+    pub(crate) fn serialize<S, T, const N: usize>(array: &[T; N], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        T: LeBytes,
+    {
+        if cfg!(target_endian = "little") {
+            serializer.serialize_bytes(as_bytes(array))
+        } else {
+            let swapped: Vec<T> = array.iter().map(|n| n.to_le()).collect();
+            serializer.serialize_bytes(as_bytes(&swapped))
+        }
+    }
+
+    #[inline(always)]
+    pub(crate) fn deserialize<'de, D, T, const N: usize>(deserializer: D) -> Result<[T; N], D::Error>
+    where
+        D: Deserializer<'de>,
+        T: LeBytes,
+    {
+        deserializer.deserialize_bytes(ArrayVisitor::<T, N>(PhantomData))
+    }
+
+    struct ArrayVisitor<T, const N: usize>(PhantomData<T>);
+
+    impl<'de, T: LeBytes, const N: usize> Visitor<'de> for ArrayVisitor<T, N> {
+        type Value = [T; N];
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            write!(formatter, "{} little-endian bytes", N * size_of::<T>())
+        }
+
+        #[inline(always)]
+        fn visit_bytes<E>(self, bytes: &[u8]) -> Result<Self::Value, E>
+        where
+            E: Error,
+        {
+            if bytes.len() != N * size_of::<T>() {
+                return Err(E::invalid_length(bytes.len(), &self));
+            }
+            let mut array = [T::default(); N];
+            // SAFETY: lengths match and LeBytes types accept any bit pattern
+            unsafe {
+                core::ptr::copy_nonoverlapping(bytes.as_ptr(), array.as_mut_ptr() as *mut u8, bytes.len());
+            }
+            if cfg!(target_endian = "big") {
+                array.iter_mut().for_each(|n| *n = n.to_le());
+            }
+            Ok(array)
+        }
+
+        // formats without a native byte string (e.g. JSON) hand us the bytes one by one
+        fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+        where
+            A: SeqAccess<'de>,
+        {
+            // exactly N * size_of::<T>() of them: an overlong sequence is rejected at the first extra
+            // element instead of being collected in full
+            let mut bytes = vec![0; N * size_of::<T>()];
+            for (i, byte) in bytes.iter_mut().enumerate() {
+                *byte = seq
+                    .next_element()?
+                    .ok_or_else(|| A::Error::invalid_length(i, &self))?;
             }
+            if seq.next_element::<u8>()?.is_some() {
+                return Err(A::Error::invalid_length(bytes.len() + 1, &self));
+            }
+            self.visit_bytes(&bytes)
         }
     }
+}
 
-    #[bench]
-    fn bench_serialize_serde_as_bytes_normal(bencher: &mut test::Bencher) {
-        let mut output_binary = vec![];
-        let mut input_packets: Vec<_> = std::iter::repeat(Packet::default()).take(512).collect();
+// `Box<[u8; N]>` for N large enough that `[u8; N]` on the stack would overflow it:
+// the visitor writes straight into an uninitialized heap allocation
+#[cfg(test)]
+mod serde_boxed_bytes_array {
+    use {
+        core::fmt,
+        serde::{
+            de::{Error, SeqAccess, Visitor},
+            Deserializer, Serializer,
+        },
+    };
+
+    // serde(with) passes the field as is, so this has to take `&Box<_>`
+    #[allow(clippy::borrowed_box)]
+    #[inline(always)]
+    pub(crate) fn serialize<S, const N: usize>(bytes: &Box<[u8; N]>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_bytes(&bytes[..])
+    }
+
+    #[inline(always)]
+    pub(crate) fn deserialize<'de, D, const N: usize>(deserializer: D) -> Result<Box<[u8; N]>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_bytes(BoxedArrayVisitor::<N>)
+    }
+
+    struct BoxedArrayVisitor<const N: usize>;
+
+    impl<'de, const N: usize> Visitor<'de> for BoxedArrayVisitor<N> {
+        type Value = Box<[u8; N]>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            write!(formatter, "[u8; {}] as bytes or a sequence", N)
+        }
+
+        #[inline(always)]
+        fn visit_bytes<E>(self, bytes: &[u8]) -> Result<Self::Value, E>
+        where
+            E: Error,
+        {
+            if bytes.len() != N {
+                return Err(E::invalid_length(bytes.len(), &self));
+            }
+            let mut array = Box::<[u8; N]>::new_uninit();
+            // SAFETY: exactly N bytes are copied into the N-byte allocation, initializing all of it
+            unsafe {
+                core::ptr::copy_nonoverlapping(bytes.as_ptr(), array.as_mut_ptr() as *mut u8, N);
+                Ok(array.assume_init())
+            }
+        }
+
+        fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+        where
+            A: SeqAccess<'de>,
+        {
+            let mut array = Box::<[u8; N]>::new_uninit();
+            let ptr = array.as_mut_ptr() as *mut u8;
+            for i in 0..N {
+                let byte = seq.next_element()?.ok_or_else(|| A::Error::invalid_length(i, &self))?;
+                // SAFETY: i < N; u8 has no drop, so bailing out above leaks nothing
+                unsafe { ptr.add(i).write(byte) };
+            }
+            if seq.next_element::<u8>()?.is_some() {
+                return Err(A::Error::invalid_length(N + 1, &self));
+            }
+            // SAFETY: the loop above wrote all N bytes
+            Ok(unsafe { array.assume_init() })
+        }
+    }
+}
+
+// `[T; N]` for any `T: Deserialize`, no `Default` or `Copy` needed: elements are written into
+// `[MaybeUninit<T>; N]`, and the ones already written are dropped if a later one fails
+#[cfg(test)]
+mod serde_array_uninit {
+    use {
+        core::{
+            fmt,
+            marker::PhantomData,
+            mem::{ManuallyDrop, MaybeUninit},
+        },
+        serde::{
+            de::{Deserialize, Error, SeqAccess, Visitor},
+            ser::{Serialize, SerializeTuple},
+            Deserializer, Serializer,
+        },
+    };
+
+    #[inline(always)]
+    pub(crate) fn serialize<S, T, const N: usize>(array: &[T; N], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        T: Serialize,
+    {
+        let mut tuple = serializer.serialize_tuple(N)?;
+        for element in array {
+            tuple.serialize_element(element)?;
+        }
+        tuple.end()
+    }
+
+    #[inline(always)]
+    pub(crate) fn deserialize<'de, D, T, const N: usize>(deserializer: D) -> Result<[T; N], D::Error>
+    where
+        D: Deserializer<'de>,
+        T: Deserialize<'de>,
+    {
+        deserializer.deserialize_tuple(N, ArrayVisitor::<T, N>(PhantomData))
+    }
+
+    // the first `len` elements are initialized
+    struct PartialArray<T, const N: usize> {
+        array: [MaybeUninit<T>; N],
+        len: usize,
+    }
+
+    impl<T, const N: usize> PartialArray<T, N> {
+        fn new() -> Self {
+            Self {
+                array: [const { MaybeUninit::uninit() }; N],
+                len: 0,
+            }
+        }
+
+        #[inline(always)]
+        fn push(&mut self, element: T) {
+            self.array[self.len].write(element);
+            self.len += 1;
+        }
+
+        fn into_array(self) -> [T; N] {
+            assert_eq!(self.len, N);
+            let this = ManuallyDrop::new(self);
+            // SAFETY: all N elements are initialized, and `this` won't drop them again
+            unsafe { (&this.array as *const [MaybeUninit<T>; N] as *const [T; N]).read() }
+        }
+    }
+
+    impl<T, const N: usize> Drop for PartialArray<T, N> {
+        fn drop(&mut self) {
+            for element in &mut self.array[..self.len] {
+                // SAFETY: only the initialized prefix is dropped
+                unsafe { element.assume_init_drop() };
+            }
+        }
+    }
+
+    struct ArrayVisitor<T, const N: usize>(PhantomData<T>);
+
+    impl<'de, T: Deserialize<'de>, const N: usize> Visitor<'de> for ArrayVisitor<T, N> {
+        type Value = [T; N];
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            write!(formatter, "an array of length {}", N)
+        }
+
+        fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+        where
+            A: SeqAccess<'de>,
+        {
+            let mut array = PartialArray::<T, N>::new();
+            while array.len < N {
+                match seq.next_element()? {
+                    Some(element) => array.push(element),
+                    None => return Err(A::Error::invalid_length(array.len, &self)),
+                }
+            }
+            Ok(array.into_array())
+        }
+    }
+}
+
+// `[[u8; N]; M]` as one N * M byte string instead of M tuples (or M length-prefixed rows)
+#[cfg(test)]
+mod serde_bytes_array_2d {
+    use {
+        core::fmt,
+        serde::{
+            de::{Error, SeqAccess, Visitor},
+            Deserializer, Serializer,
+        },
+    };
+
+    #[inline(always)]
+    pub(crate) fn serialize<S, const N: usize, const M: usize>(array: &[[u8; N]; M], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_bytes(array.as_flattened())
+    }
+
+    #[inline(always)]
+    pub(crate) fn deserialize<'de, D, const N: usize, const M: usize>(deserializer: D) -> Result<[[u8; N]; M], D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_bytes(ArrayVisitor::<N, M>)
+    }
+
+    struct ArrayVisitor<const N: usize, const M: usize>;
+
+    impl<'de, const N: usize, const M: usize> Visitor<'de> for ArrayVisitor<N, M> {
+        type Value = [[u8; N]; M];
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            write!(formatter, "[[u8; {}]; {}] as {} bytes", N, M, N * M)
+        }
+
+        #[inline(always)]
+        fn visit_bytes<E>(self, bytes: &[u8]) -> Result<Self::Value, E>
+        where
+            E: Error,
+        {
+            if bytes.len() != N * M {
+                return Err(E::invalid_length(bytes.len(), &self));
+            }
+            let mut array = [[0; N]; M];
+            array.as_flattened_mut().copy_from_slice(bytes);
+            Ok(array)
+        }
+
+        fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+        where
+            A: SeqAccess<'de>,
+        {
+            let mut array = [[0; N]; M];
+            for (i, byte) in array.as_flattened_mut().iter_mut().enumerate() {
+                *byte = seq
+                    .next_element()?
+                    .ok_or_else(|| A::Error::invalid_length(i, &self))?;
+            }
+            if seq.next_element::<u8>()?.is_some() {
+                return Err(A::Error::invalid_length(N * M + 1, &self));
+            }
+            Ok(array)
+        }
+    }
+}
+
+// `Vec<[u8; N]>` as the element count plus one contiguous byte string, instead of one length
+// prefix (or one tuple walk) per element
+#[cfg(test)]
+mod serde_bytes_array_vec {
+    use {
+        core::fmt,
+        serde::{
+            de::{DeserializeSeed, Error, SeqAccess, Visitor},
+            ser::SerializeTuple,
+            Deserializer, Serialize, Serializer,
+        },
+    };
+
+    struct FlatBytes<'a>(&'a [u8]);
+
+    impl Serialize for FlatBytes<'_> {
+        #[inline(always)]
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            serializer.serialize_bytes(self.0)
+        }
+    }
+
+    #[inline(always)]
+    pub(crate) fn serialize<S, const N: usize>(vec: &[[u8; N]], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if N == 0 && !vec.is_empty() {
+            return Err(serde::ser::Error::custom("a nonempty Vec<[u8; 0]> has no bytes to check its count against"));
+        }
+        let mut tuple = serializer.serialize_tuple(2)?;
+        tuple.serialize_element(&(vec.len() as u64))?;
+        tuple.serialize_element(&FlatBytes(vec.as_flattened()))?;
+        tuple.end()
+    }
+
+    #[inline(always)]
+    pub(crate) fn deserialize<'de, D, const N: usize>(deserializer: D) -> Result<Vec<[u8; N]>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_tuple(2, VecVisitor::<N>)
+    }
+
+    struct VecVisitor<const N: usize>;
+
+    impl<'de, const N: usize> Visitor<'de> for VecVisitor<N> {
+        type Value = Vec<[u8; N]>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            write!(formatter, "an element count and the Vec<[u8; {}]> as bytes", N)
+        }
+
+        fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+        where
+            A: SeqAccess<'de>,
+        {
+            let count: u64 = seq.next_element()?.ok_or_else(|| A::Error::invalid_length(0, &self))?;
+            let too_large = || A::Error::custom(format_args!("{} elements of [u8; {}] is too large", count, N));
+            let count = usize::try_from(count).map_err(|_| too_large())?;
+            // with no bytes to check it against, any count would be taken on trust
+            if N == 0 && count != 0 {
+                return Err(A::Error::invalid_value(serde::de::Unexpected::Unsigned(count as u64), &"0 elements of [u8; 0]"));
+            }
+            let len = count.checked_mul(N).ok_or_else(too_large)?;
+            seq.next_element_seed(ChunksSeed::<N> { len })?
+                .ok_or_else(|| A::Error::invalid_length(1, &self))
+        }
+    }
+
+    // checks the byte string against the count read before it, then splits it up
+    struct ChunksSeed<const N: usize> {
+        len: usize,
+    }
+
+    impl<'de, const N: usize> DeserializeSeed<'de> for ChunksSeed<N> {
+        type Value = Vec<[u8; N]>;
+
+        fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            deserializer.deserialize_bytes(self)
+        }
+    }
+
+    impl<'de, const N: usize> Visitor<'de> for ChunksSeed<N> {
+        type Value = Vec<[u8; N]>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            write!(formatter, "{} bytes", self.len)
+        }
+
+        #[inline(always)]
+        fn visit_bytes<E>(self, bytes: &[u8]) -> Result<Self::Value, E>
+        where
+            E: Error,
+        {
+            if bytes.len() != self.len {
+                return Err(E::invalid_length(bytes.len(), &self));
+            }
+            // chunks_exact(0) would panic, and the count is always 0 then
+            if N == 0 {
+                return Ok(vec![]);
+            }
+            Ok(bytes
+                .chunks_exact(N)
+                .map(|chunk| chunk.try_into().unwrap())
+                .collect())
+        }
+
+        fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+        where
+            A: SeqAccess<'de>,
+        {
+            let mut bytes = Vec::with_capacity(self.len.min(4096));
+            while bytes.len() < self.len {
+                let byte = seq
+                    .next_element()?
+                    .ok_or_else(|| A::Error::invalid_length(bytes.len(), &self))?;
+                bytes.push(byte);
+            }
+            if seq.next_element::<u8>()?.is_some() {
+                return Err(A::Error::invalid_length(self.len + 1, &self));
+            }
+            self.visit_bytes(&bytes)
+        }
+    }
+}
+
+// compact-u16 ("short_vec") length prefix as used on the Solana wire: 1 to 3 bytes of 7-bit groups,
+// low group first, high bit set when another byte follows. replaces bincode's fixed 8-byte length.
+#[cfg(test)]
+mod short_vec {
+    use {
+        core::{fmt, marker::PhantomData},
+        serde::{
+            de::{Deserialize, Error, SeqAccess, Visitor},
+            ser::{self, Serialize, SerializeTuple},
+            Deserializer, Serializer,
+        },
+    };
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct ShortU16(pub u16);
+
+    impl Serialize for ShortU16 {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            // bincode writes tuple elements back to back without a length, so the declared
+            // length doesn't have to match the 1 to 3 bytes actually written
+            let mut tuple = serializer.serialize_tuple(1)?;
+            let mut rem = self.0;
+            loop {
+                let byte = (rem & 0x7f) as u8;
+                rem >>= 7;
+                if rem == 0 {
+                    tuple.serialize_element(&byte)?;
+                    break;
+                }
+                tuple.serialize_element(&(byte | 0x80))?;
+            }
+            tuple.end()
+        }
+    }
+
+    impl<'de> Deserialize<'de> for ShortU16 {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            deserializer.deserialize_tuple(3, ShortU16Visitor)
+        }
+    }
+
+    struct ShortU16Visitor;
+
+    impl<'de> Visitor<'de> for ShortU16Visitor {
+        type Value = ShortU16;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a compact-u16")
+        }
+
+        fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+        where
+            A: SeqAccess<'de>,
+        {
+            let mut value: u16 = 0;
+            for nth in 0..3 {
+                let byte: u8 = seq.next_element()?.ok_or_else(|| A::Error::invalid_length(nth, &self))?;
+                // a zero byte after the first would encode the same value in more bytes than needed
+                if nth > 0 && byte == 0 {
+                    return Err(A::Error::custom("compact-u16 is not in its shortest form"));
+                }
+                // the third byte only has room for bits 14 and 15
+                if nth == 2 && byte > 0x03 {
+                    return Err(A::Error::custom("compact-u16 overflows u16"));
+                }
+                value |= u16::from(byte & 0x7f) << (nth * 7);
+                if byte & 0x80 == 0 {
+                    return Ok(ShortU16(value));
+                }
+            }
+            unreachable!("the third byte is at most 0x03, so it never has a continuation bit")
+        }
+    }
+
+    fn short_len<E: ser::Error>(len: usize) -> Result<ShortU16, E> {
+        u16::try_from(len)
+            .map(ShortU16)
+            .map_err(|_| E::custom(format_args!("length {} doesn't fit in a compact-u16", len)))
+    }
+
+    #[inline(always)]
+    pub(crate) fn serialize<S, T>(elements: &[T], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        T: Serialize,
+    {
+        let mut tuple = serializer.serialize_tuple(1)?;
+        tuple.serialize_element(&short_len::<S::Error>(elements.len())?)?;
+        for element in elements {
+            tuple.serialize_element(element)?;
+        }
+        tuple.end()
+    }
+
+    #[inline(always)]
+    pub(crate) fn deserialize<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: Deserialize<'de>,
+    {
+        deserializer.deserialize_tuple(usize::MAX, ShortVecVisitor::<T>(PhantomData))
+    }
+
+    struct ShortVecVisitor<T>(PhantomData<T>);
+
+    impl<'de, T: Deserialize<'de>> Visitor<'de> for ShortVecVisitor<T> {
+        type Value = Vec<T>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a compact-u16 length followed by that many elements")
+        }
+
+        fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+        where
+            A: SeqAccess<'de>,
+        {
+            let ShortU16(len) = seq.next_element()?.ok_or_else(|| A::Error::invalid_length(0, &self))?;
+            let len = usize::from(len);
+            let mut elements = Vec::with_capacity(len);
+            for i in 0..len {
+                elements.push(seq.next_element()?.ok_or_else(|| A::Error::invalid_length(i + 1, &self))?);
+            }
+            Ok(elements)
+        }
+    }
+
+    // same wire format for `Vec<[u8; N]>` with any N: each array is N raw bytes, with no per-element
+    // length, and without serde's 32-element limit for arrays
+    pub(crate) mod bytes_array {
+        use {
+            super::{short_len, ShortU16},
+            core::fmt,
+            serde::{
+                de::{DeserializeSeed, Error, SeqAccess, Visitor},
+                ser::SerializeTuple,
+                Deserializer, Serialize, Serializer,
+            },
+        };
+
+        struct Tuple<'a, const N: usize>(&'a [u8; N]);
+
+        impl<const N: usize> Serialize for Tuple<'_, N> {
+            #[inline(always)]
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: Serializer,
+            {
+                crate::serde_array_uninit::serialize(self.0, serializer)
+            }
+        }
+
+        struct TupleSeed<const N: usize>;
+
+        impl<'de, const N: usize> DeserializeSeed<'de> for TupleSeed<N> {
+            type Value = [u8; N];
+
+            #[inline(always)]
+            fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                crate::serde_array_uninit::deserialize(deserializer)
+            }
+        }
+
+        #[inline(always)]
+        pub(crate) fn serialize<S, const N: usize>(elements: &[[u8; N]], serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            let mut tuple = serializer.serialize_tuple(1)?;
+            tuple.serialize_element(&short_len::<S::Error>(elements.len())?)?;
+            for element in elements {
+                tuple.serialize_element(&Tuple(element))?;
+            }
+            tuple.end()
+        }
+
+        #[inline(always)]
+        pub(crate) fn deserialize<'de, D, const N: usize>(deserializer: D) -> Result<Vec<[u8; N]>, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            deserializer.deserialize_tuple(usize::MAX, ShortVecVisitor::<N>)
+        }
+
+        struct ShortVecVisitor<const N: usize>;
+
+        impl<'de, const N: usize> Visitor<'de> for ShortVecVisitor<N> {
+            type Value = Vec<[u8; N]>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                write!(formatter, "a compact-u16 length followed by that many [u8; {}]", N)
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                let ShortU16(len) = seq.next_element()?.ok_or_else(|| A::Error::invalid_length(0, &self))?;
+                let len = usize::from(len);
+                let mut elements = Vec::with_capacity(len);
+                for i in 0..len {
+                    elements.push(
+                        seq.next_element_seed(TupleSeed::<N>)?
+                            .ok_or_else(|| A::Error::invalid_length(i + 1, &self))?,
+                    );
+                }
+                Ok(elements)
+            }
+        }
+    }
+}
+
+// `[bool; N]` packed into ceil(N / 8) bytes, bit i in byte i / 8 at position i % 8.
+// the unused high bits of the last byte must be zero, so every bool array has exactly one encoding
+#[cfg(test)]
+mod serde_bool_bitset {
+    use {
+        core::fmt,
+        serde::{
+            de::{Error, SeqAccess, Visitor},
+            Deserializer, Serializer,
+        },
+    };
+
+    #[inline(always)]
+    pub(crate) fn serialize<S, const N: usize>(bits: &[bool; N], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        // up to 512 bools are packed on the stack
+        let mut stack = [0u8; 64];
+        let mut heap;
+        let bytes = match N.div_ceil(8) {
+            len if len <= stack.len() => &mut stack[..len],
+            len => {
+                heap = vec![0u8; len];
+                &mut heap[..]
+            }
+        };
+        for (i, _) in bits.iter().enumerate().filter(|(_, bit)| **bit) {
+            bytes[i / 8] |= 1 << (i % 8);
+        }
+        serializer.serialize_bytes(bytes)
+    }
+
+    #[inline(always)]
+    pub(crate) fn deserialize<'de, D, const N: usize>(deserializer: D) -> Result<[bool; N], D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_bytes(BitsetVisitor::<N>)
+    }
+
+    struct BitsetVisitor<const N: usize>;
+
+    impl<'de, const N: usize> Visitor<'de> for BitsetVisitor<N> {
+        type Value = [bool; N];
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            write!(formatter, "[bool; {}] packed into {} bytes", N, N.div_ceil(8))
+        }
+
+        #[inline(always)]
+        fn visit_bytes<E>(self, bytes: &[u8]) -> Result<Self::Value, E>
+        where
+            E: Error,
+        {
+            if bytes.len() != N.div_ceil(8) {
+                return Err(E::invalid_length(bytes.len(), &self));
+            }
+            if !N.is_multiple_of(8) && bytes[bytes.len() - 1] >> (N % 8) != 0 {
+                return Err(E::custom(format_args!("padding bits after bit {} are not zero", N)));
+            }
+            let mut bits = [false; N];
+            for (i, bit) in bits.iter_mut().enumerate() {
+                *bit = bytes[i / 8] & (1 << (i % 8)) != 0;
+            }
+            Ok(bits)
+        }
+
+        fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+        where
+            A: SeqAccess<'de>,
+        {
+            let len = N.div_ceil(8);
+            let mut bytes = Vec::with_capacity(len);
+            for i in 0..len {
+                bytes.push(seq.next_element()?.ok_or_else(|| A::Error::invalid_length(i, &self))?);
+            }
+            if seq.next_element::<u8>()?.is_some() {
+                return Err(A::Error::invalid_length(len + 1, &self));
+            }
+            self.visit_bytes(&bytes)
+        }
+    }
+}
+
+// `[u8; N]` of which only the first `len` bytes are in use, like `buffer[..meta.size]` of a packet.
+// only that prefix is written and the rest comes back zeroed. a `with` module can't see a sibling
+// `size` field, so the length lives next to the array in this wrapper instead.
+mod sized_buffer {
+    use {
+        core::{fmt, ops::Deref},
+        serde::{
+            de::{Error, SeqAccess, Visitor},
+            Deserialize, Deserializer, Serialize, Serializer,
+        },
+    };
+
+    #[derive(Clone, Copy)]
+    pub struct SizedBuffer<const N: usize> {
+        len: usize,
+        bytes: [u8; N],
+    }
+
+    impl<const N: usize> SizedBuffer<N> {
+        /// `None` if `data` doesn't fit into `N` bytes
+        #[inline(always)]
+        pub fn from_slice(data: &[u8]) -> Option<Self> {
+            let mut buffer = Self::default();
+            buffer.bytes.get_mut(..data.len())?.copy_from_slice(data);
+            buffer.len = data.len();
+            Some(buffer)
+        }
+
+        /// the whole array, used or not, e.g. to receive into
+        #[inline(always)]
+        #[cfg_attr(not(test), allow(dead_code))]
+        pub fn as_array_mut(&mut self) -> &mut [u8; N] {
+            &mut self.bytes
+        }
+
+        /// panics if `len > N`
+        #[inline(always)]
+        #[cfg_attr(not(test), allow(dead_code))]
+        pub fn set_len(&mut self, len: usize) {
+            assert!(len <= N, "len {} exceeds buffer size {}", len, N);
+            self.len = len;
+        }
+    }
+
+    impl<const N: usize> Default for SizedBuffer<N> {
+        #[inline(always)]
+        fn default() -> Self {
+            Self { len: 0, bytes: [0; N] }
+        }
+    }
+
+    impl<const N: usize> Deref for SizedBuffer<N> {
+        type Target = [u8];
+
+        #[inline(always)]
+        fn deref(&self) -> &Self::Target {
+            &self.bytes[..self.len]
+        }
+    }
+
+    // whatever sits past `len` isn't part of the value
+    impl<const N: usize> PartialEq for SizedBuffer<N> {
+        fn eq(&self, other: &Self) -> bool {
+            **self == **other
+        }
+    }
+
+    impl<const N: usize> Eq for SizedBuffer<N> {}
+
+    impl<const N: usize> fmt::Debug for SizedBuffer<N> {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "SizedBuffer<{}>({:?})", N, &**self)
+        }
+    }
+
+    impl<const N: usize> Serialize for SizedBuffer<N> {
+        #[inline(always)]
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            serializer.serialize_bytes(self)
+        }
+    }
+
+    impl<'de, const N: usize> Deserialize<'de> for SizedBuffer<N> {
+        #[inline(always)]
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            deserializer.deserialize_bytes(SizedBufferVisitor::<N>)
+        }
+    }
+
+    struct SizedBufferVisitor<const N: usize>;
+
+    impl<'de, const N: usize> Visitor<'de> for SizedBufferVisitor<N> {
+        type Value = SizedBuffer<N>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            write!(formatter, "at most {} bytes", N)
+        }
+
+        #[inline(always)]
+        fn visit_bytes<E>(self, bytes: &[u8]) -> Result<Self::Value, E>
+        where
+            E: Error,
+        {
+            SizedBuffer::from_slice(bytes).ok_or_else(|| E::invalid_length(bytes.len(), &self))
+        }
+
+        fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+        where
+            A: SeqAccess<'de>,
+        {
+            let mut buffer = SizedBuffer::<N>::default();
+            while let Some(byte) = seq.next_element()? {
+                if buffer.len == N {
+                    return Err(A::Error::invalid_length(N + 1, &self));
+                }
+                buffer.bytes[buffer.len] = byte;
+                buffer.len += 1;
+            }
+            Ok(buffer)
+        }
+    }
+}
+
+// just enough of pcap and pcapng to pull UDP payloads out of a capture, so real traffic can be
+// replayed through the benches (`BENCH_PAYLOAD=pcap`), and to write bench packets back out as one.
+// reading understands Ethernet (with 802.1Q tags), BSD loopback, raw IP and Linux cooked captures;
+// anything else, including IP fragments and IPv6 extension headers, is skipped
+mod pcap {
+    use std::{
+        io::{self, Write},
+        net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+        path::Path,
+    };
+
+    const PCAP_MAGIC_MICROS: u32 = 0xa1b2_c3d4;
+    const PCAP_MAGIC_NANOS: u32 = 0xa1b2_3c4d;
+    const PCAPNG_SECTION_HEADER: u32 = 0x0a0d_0d0a;
+    const PCAPNG_BYTE_ORDER_MAGIC: u32 = 0x1a2b_3c4d;
+    const PCAPNG_INTERFACE_DESCRIPTION: u32 = 1;
+    const PCAPNG_SIMPLE_PACKET: u32 = 3;
+    const PCAPNG_ENHANCED_PACKET: u32 = 6;
+    // what tcpdump and Wireshark write these days, rather than the old 65535
+    const SNAPLEN: u32 = 262_144;
+
+    pub const LINKTYPE_NULL: u16 = 0;
+    pub const LINKTYPE_ETHERNET: u16 = 1;
+    pub const LINKTYPE_RAW: u16 = 101;
+    pub const LINKTYPE_LINUX_SLL: u16 = 113;
+
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub struct Datagram {
+        pub source: SocketAddr,
+        pub destination: SocketAddr,
+        pub payload: Vec<u8>,
+    }
+
+    fn invalid(message: &str) -> io::Error {
+        io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+    }
+
+    pub fn read_file(path: impl AsRef<Path>) -> io::Result<Vec<Datagram>> {
+        read(&std::fs::read(path)?)
+    }
+
+    /// every UDP datagram in a classic pcap or pcapng capture, in capture order
+    pub fn read(bytes: &[u8]) -> io::Result<Vec<Datagram>> {
+        let magic = bytes.get(..4).ok_or_else(|| invalid("truncated file header"))?;
+        let magic = u32::from_le_bytes(magic.try_into().unwrap());
+        if magic == PCAPNG_SECTION_HEADER {
+            read_pcapng(bytes)
+        } else {
+            read_pcap(bytes)
+        }
+    }
+
+    // captures come in either byte order, so every field goes through this
+    #[derive(Clone, Copy)]
+    struct Endian(bool);
+
+    impl Endian {
+        fn u16(self, bytes: &[u8], at: usize) -> io::Result<u16> {
+            let field = bytes.get(at..at + 2).ok_or_else(|| invalid("truncated header"))?.try_into().unwrap();
+            Ok(if self.0 { u16::from_be_bytes(field) } else { u16::from_le_bytes(field) })
+        }
+
+        fn u32(self, bytes: &[u8], at: usize) -> io::Result<u32> {
+            let field = bytes.get(at..at + 4).ok_or_else(|| invalid("truncated header"))?.try_into().unwrap();
+            Ok(if self.0 { u32::from_be_bytes(field) } else { u32::from_le_bytes(field) })
+        }
+    }
+
+    fn read_pcap(bytes: &[u8]) -> io::Result<Vec<Datagram>> {
+        let endian = match Endian(false).u32(bytes, 0)? {
+            PCAP_MAGIC_MICROS | PCAP_MAGIC_NANOS => Endian(false),
+            magic if magic.swap_bytes() == PCAP_MAGIC_MICROS || magic.swap_bytes() == PCAP_MAGIC_NANOS => Endian(true),
+            _ => return Err(invalid("neither pcap nor pcapng")),
+        };
+        let link_type = endian.u32(bytes, 20)? as u16;
+
+        let mut datagrams = vec![];
+        let mut offset = 24;
+        while offset < bytes.len() {
+            let captured = endian.u32(bytes, offset + 8)? as usize;
+            let frame = bytes
+                .get(offset + 16..offset + 16 + captured)
+                .ok_or_else(|| invalid("truncated packet record"))?;
+            datagrams.extend(udp_in_frame(link_type, frame));
+            offset += 16 + captured;
+        }
+        Ok(datagrams)
+    }
+
+    fn read_pcapng(bytes: &[u8]) -> io::Result<Vec<Datagram>> {
+        let mut endian = Endian(false);
+        let mut link_types = vec![];
+
+        let mut datagrams = vec![];
+        let mut offset = 0;
+        while offset < bytes.len() {
+            let block_type = endian.u32(bytes, offset)?;
+            if block_type == PCAPNG_SECTION_HEADER {
+                // each section says its own byte order, and restarts interface numbering
+                endian = match Endian(false).u32(bytes, offset + 8)? {
+                    PCAPNG_BYTE_ORDER_MAGIC => Endian(false),
+                    magic if magic.swap_bytes() == PCAPNG_BYTE_ORDER_MAGIC => Endian(true),
+                    _ => return Err(invalid("bad pcapng byte-order magic")),
+                };
+                link_types.clear();
+            }
+            let length = endian.u32(bytes, offset + 4)? as usize;
+            if length < 12 || !length.is_multiple_of(4) {
+                return Err(invalid("bad pcapng block length"));
+            }
+            let block = bytes.get(offset..offset + length).ok_or_else(|| invalid("truncated pcapng block"))?;
+            match block_type {
+                PCAPNG_INTERFACE_DESCRIPTION => link_types.push(endian.u16(block, 8)?),
+                PCAPNG_ENHANCED_PACKET => {
+                    let interface = endian.u32(block, 8)? as usize;
+                    let captured = endian.u32(block, 20)? as usize;
+                    let frame = block.get(28..28 + captured).ok_or_else(|| invalid("truncated enhanced packet block"))?;
+                    let link_type = *link_types.get(interface).ok_or_else(|| invalid("packet on unknown interface"))?;
+                    datagrams.extend(udp_in_frame(link_type, frame));
+                }
+                PCAPNG_SIMPLE_PACKET => {
+                    let original = endian.u32(block, 8)? as usize;
+                    let frame = &block[12..12 + original.min(length.saturating_sub(16))];
+                    let link_type = *link_types.first().ok_or_else(|| invalid("packet on unknown interface"))?;
+                    datagrams.extend(udp_in_frame(link_type, frame));
+                }
+                _ => {}
+            }
+            offset += length;
+        }
+        Ok(datagrams)
+    }
+
+    fn udp_in_frame(link_type: u16, frame: &[u8]) -> Option<Datagram> {
+        let ip = match link_type {
+            LINKTYPE_ETHERNET => {
+                let mut at = 12;
+                // 802.1Q / 802.1ad tags
+                while matches!(frame.get(at..at + 2)?, [0x81, 0x00] | [0x88, 0xa8]) {
+                    at += 4;
+                }
+                match frame.get(at..at + 2)? {
+                    [0x08, 0x00] | [0x86, 0xdd] => frame.get(at + 2..)?,
+                    _ => return None,
+                }
+            }
+            // the address family is in the capturing host's byte order, but all that matters
+            // here is the IP version nibble that follows
+            LINKTYPE_NULL => frame.get(4..)?,
+            LINKTYPE_RAW => frame,
+            LINKTYPE_LINUX_SLL => match frame.get(14..16)? {
+                [0x08, 0x00] | [0x86, 0xdd] => frame.get(16..)?,
+                _ => return None,
+            },
+            _ => return None,
+        };
+        udp_in_ip(ip)
+    }
+
+    fn udp_in_ip(ip: &[u8]) -> Option<Datagram> {
+        const UDP: u8 = 17;
+
+        let (source, destination, udp) = match ip.first()? >> 4 {
+            4 => {
+                let header_length = (ip[0] & 0x0f) as usize * 4;
+                let fragment = u16::from_be_bytes(ip.get(6..8)?.try_into().unwrap());
+                // more fragments, or not the first one
+                if *ip.get(9)? != UDP || fragment & 0x3fff != 0 {
+                    return None;
+                }
+                let total_length = u16::from_be_bytes(ip[2..4].try_into().unwrap()) as usize;
+                let source: [u8; 4] = ip.get(12..16)?.try_into().unwrap();
+                let destination: [u8; 4] = ip.get(16..20)?.try_into().unwrap();
+                (
+                    IpAddr::V4(Ipv4Addr::from(source)),
+                    IpAddr::V4(Ipv4Addr::from(destination)),
+                    ip.get(header_length..total_length.min(ip.len()))?,
+                )
+            }
+            6 => {
+                if *ip.get(6)? != UDP {
+                    return None;
+                }
+                let payload_length = u16::from_be_bytes(ip[4..6].try_into().unwrap()) as usize;
+                let source: [u8; 16] = ip.get(8..24)?.try_into().unwrap();
+                let destination: [u8; 16] = ip.get(24..40)?.try_into().unwrap();
+                (
+                    IpAddr::V6(Ipv6Addr::from(source)),
+                    IpAddr::V6(Ipv6Addr::from(destination)),
+                    ip.get(40..(40 + payload_length).min(ip.len()))?,
+                )
+            }
+            _ => return None,
+        };
+        let source_port = u16::from_be_bytes(udp.get(0..2)?.try_into().unwrap());
+        let destination_port = u16::from_be_bytes(udp.get(2..4)?.try_into().unwrap());
+        let length = u16::from_be_bytes(udp.get(4..6)?.try_into().unwrap()) as usize;
+        Some(Datagram {
+            source: SocketAddr::new(source, source_port),
+            destination: SocketAddr::new(destination, destination_port),
+            // a snap length shorter than the datagram leaves only what was captured
+            payload: udp.get(8..length.min(udp.len()))?.to_vec(),
+        })
+    }
+
+    /// a classic little-endian pcap with each datagram in a BSD loopback frame, which is what
+    /// Wireshark shows for captures on lo0. source and destination must be the same IP version
+    #[cfg_attr(not(feature = "format-bincode"), allow(dead_code))]
+    pub fn write(mut writer: impl Write, datagrams: &[Datagram]) -> io::Result<()> {
+        // AF_INET, and the AF_INET6 of the BSDs; Wireshark knows all of them
+        const AF_INET: u32 = 2;
+        const AF_INET6: u32 = 24;
+
+        let mut header = Vec::with_capacity(24);
+        header.extend_from_slice(&PCAP_MAGIC_MICROS.to_le_bytes());
+        header.extend_from_slice(&2u16.to_le_bytes());
+        header.extend_from_slice(&4u16.to_le_bytes());
+        header.extend_from_slice(&[0; 8]);
+        header.extend_from_slice(&SNAPLEN.to_le_bytes());
+        header.extend_from_slice(&u32::from(LINKTYPE_NULL).to_le_bytes());
+        writer.write_all(&header)?;
+
+        let mut frame = vec![];
+        for (i, datagram) in datagrams.iter().enumerate() {
+            let udp_length = u16::try_from(8 + datagram.payload.len()).map_err(|_| invalid("datagram too large"))?;
+            let mut udp = Vec::with_capacity(udp_length as usize);
+            udp.extend_from_slice(&datagram.source.port().to_be_bytes());
+            udp.extend_from_slice(&datagram.destination.port().to_be_bytes());
+            udp.extend_from_slice(&udp_length.to_be_bytes());
+            udp.extend_from_slice(&[0, 0]);
+            udp.extend_from_slice(&datagram.payload);
+
+            frame.clear();
+            match (datagram.source.ip(), datagram.destination.ip()) {
+                (IpAddr::V4(source), IpAddr::V4(destination)) => {
+                    frame.extend_from_slice(&AF_INET.to_le_bytes());
+                    let total_length = u16::try_from(20 + udp.len()).map_err(|_| invalid("datagram too large"))?;
+                    let mut ip = [0; 20];
+                    ip[0] = 0x45;
+                    ip[2..4].copy_from_slice(&total_length.to_be_bytes());
+                    ip[8] = 64;
+                    ip[9] = 17;
+                    ip[12..16].copy_from_slice(&source.octets());
+                    ip[16..20].copy_from_slice(&destination.octets());
+                    let checksum = !ones_complement_sum(0, &ip);
+                    ip[10..12].copy_from_slice(&checksum.to_be_bytes());
+                    // a zero UDP checksum means "none" over IPv4
+                    frame.extend_from_slice(&ip);
+                }
+                (IpAddr::V6(source), IpAddr::V6(destination)) => {
+                    frame.extend_from_slice(&AF_INET6.to_le_bytes());
+                    frame.extend_from_slice(&[0x60, 0, 0, 0]);
+                    frame.extend_from_slice(&udp_length.to_be_bytes());
+                    frame.extend_from_slice(&[17, 64]);
+                    frame.extend_from_slice(&source.octets());
+                    frame.extend_from_slice(&destination.octets());
+                    // mandatory over IPv6: pseudo header of addresses, length and next header
+                    let mut sum = ones_complement_sum(0, &source.octets());
+                    sum = ones_complement_sum(sum, &destination.octets());
+                    sum = ones_complement_sum(sum, &u32::from(udp_length).to_be_bytes());
+                    sum = ones_complement_sum(sum, &[0, 0, 0, 17]);
+                    let checksum = match !ones_complement_sum(sum, &udp) {
+                        0 => 0xffff,
+                        checksum => checksum,
+                    };
+                    udp[6..8].copy_from_slice(&checksum.to_be_bytes());
+                }
+                _ => return Err(io::Error::new(io::ErrorKind::InvalidInput, "source and destination differ in IP version")),
+            }
+            frame.extend_from_slice(&udp);
+
+            // one datagram per microsecond from the epoch, so the order survives any re-sorting
+            let mut record = [0; 16];
+            record[0..4].copy_from_slice(&((i / 1_000_000) as u32).to_le_bytes());
+            record[4..8].copy_from_slice(&((i % 1_000_000) as u32).to_le_bytes());
+            record[8..12].copy_from_slice(&(frame.len() as u32).to_le_bytes());
+            record[12..16].copy_from_slice(&(frame.len() as u32).to_le_bytes());
+            writer.write_all(&record)?;
+            writer.write_all(&frame)?;
+        }
+        writer.flush()
+    }
+
+    #[cfg_attr(not(feature = "format-bincode"), allow(dead_code))]
+    fn ones_complement_sum(mut sum: u16, bytes: &[u8]) -> u16 {
+        for chunk in bytes.chunks(2) {
+            let word = u16::from_be_bytes([chunk[0], chunk.get(1).copied().unwrap_or(0)]);
+            let (added, carry) = sum.overflowing_add(word);
+            sum = added + carry as u16;
+        }
+        sum
+    }
+}
+
+// what goes into the packet buffers. `Packet::default()` is all zeroes, which compresses well and
+// keeps every branch predictable, so the benches fill their packets from one of these instead:
+//
+//   BENCH_PAYLOAD=zero|random|zero-tail|histogram|pcap  (zero if unset)
+//   BENCH_SEED=<u64>                                    (fixed default, so runs are comparable)
+//   BENCH_HISTOGRAM=<file>                              ("size weight" per line, for histogram)
+//   BENCH_PCAP=<file>                                   (pcap or pcapng capture, for pcap)
+//
+// the choice is printed to stderr once per run so it ends up next to the numbers
+mod payload {
+    #[cfg(all(test, feature = "packet-meta"))]
+    use crate::packet_meta::Meta;
+    use std::{
+        fmt,
+        io::Write,
+        net::{IpAddr, Ipv4Addr, SocketAddr},
+        sync::OnceLock,
+    };
+
+    pub const DEFAULT_SEED: u64 = 0x2545_f491_4f6c_dd1d;
+
+    /// where generated packets claim to come from
+    #[cfg_attr(not(feature = "format-bincode"), allow(dead_code))]
+    pub const SYNTHETIC_SOURCE: SocketAddr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)), 8001);
+
+    // made-up but plausible: mostly vote-sized packets, some transfers, a few full ones
+    const DEFAULT_HISTOGRAM: &[(usize, u32)] = &[(176, 30), (232, 30), (400, 15), (700, 10), (1000, 10), (1232, 5)];
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum Payload {
+        Zero,
+        Random,
+        // random bytes up to a quarter of the buffer, zeroes after that
+        ZeroTail,
+        // random bytes up to a size drawn from the histogram, zeroes after that
+        Histogram,
+        // the UDP payloads of a capture, over and over; the seed isn't used
+        Pcap,
+    }
+
+    impl fmt::Display for Payload {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str(match self {
+                Payload::Zero => "zero",
+                Payload::Random => "random",
+                Payload::ZeroTail => "zero-tail",
+                Payload::Histogram => "histogram",
+                Payload::Pcap => "pcap",
+            })
+        }
+    }
+
+    pub struct Config {
+        pub payload: Payload,
+        pub seed: u64,
+        pub histogram: Vec<(usize, u32)>,
+        pub capture: Vec<crate::pcap::Datagram>,
+    }
+
+    pub fn config() -> &'static Config {
+        static CONFIG: OnceLock<Config> = OnceLock::new();
+        CONFIG.get_or_init(|| {
+            let payload = match std::env::var("BENCH_PAYLOAD").as_deref() {
+                Err(_) | Ok("zero") => Payload::Zero,
+                Ok("random") => Payload::Random,
+                Ok("zero-tail") => Payload::ZeroTail,
+                Ok("histogram") => Payload::Histogram,
+                Ok("pcap") => Payload::Pcap,
+                Ok(other) => panic!("unknown BENCH_PAYLOAD: {}", other),
+            };
+            let seed = std::env::var("BENCH_SEED")
+                .map(|seed| seed.parse().expect("BENCH_SEED is not a u64"))
+                .unwrap_or(DEFAULT_SEED);
+            let (histogram, source) = match std::env::var("BENCH_HISTOGRAM") {
+                Ok(path) => {
+                    let text = std::fs::read_to_string(&path).unwrap();
+                    (parse_histogram(&text), path)
+                }
+                Err(_) => (DEFAULT_HISTOGRAM.to_vec(), "built-in".to_string()),
+            };
+            let mut capture = vec![];
+            // straight to the stream: libtest captures eprintln!() even for benches
+            let mut stderr = std::io::stderr();
+            match payload {
+                Payload::Histogram => writeln!(stderr, "payload: {}, seed: {}, histogram: {}", payload, seed, source),
+                Payload::Pcap => {
+                    let path = std::env::var("BENCH_PCAP").expect("BENCH_PAYLOAD=pcap needs BENCH_PCAP");
+                    capture = crate::pcap::read_file(&path).unwrap();
+                    let skipped = capture.len();
+                    capture.retain(|datagram| datagram.payload.len() <= crate::PACKET_DATA_SIZE);
+                    assert!(!capture.is_empty(), "no UDP payloads of up to {} bytes in {}", crate::PACKET_DATA_SIZE, path);
+                    let skipped = skipped - capture.len();
+                    writeln!(stderr, "payload: {}, file: {}, datagrams: {} ({} oversized skipped)", payload, path, capture.len(), skipped)
+                }
+                _ => writeln!(stderr, "payload: {}, seed: {}", payload, seed),
+            }
+            .unwrap();
+            Config {
+                payload,
+                seed,
+                histogram,
+                capture,
+            }
+        })
+    }
+
+    // each bucket covers the sizes above the previous bucket up to and including its own
+    pub fn parse_histogram(text: &str) -> Vec<(usize, u32)> {
+        let histogram: Vec<_> = text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(|line| {
+                let mut fields = line.split_whitespace().map(|field| field.parse::<u64>());
+                match (fields.next(), fields.next(), fields.next()) {
+                    (Some(Ok(size)), Some(Ok(weight)), None) => (size as usize, weight as u32),
+                    _ => panic!("bad histogram line: {:?}", line),
+                }
+            })
+            .collect();
+        assert!(histogram.windows(2).all(|pair| pair[0].0 < pair[1].0), "histogram sizes must be increasing");
+        assert!(histogram.iter().any(|(_, weight)| *weight > 0), "histogram is empty");
+        histogram
+    }
+
+    // xorshift64*: plenty for filler bytes and needs no dependency
+    pub struct Rng(u64);
+
+    impl Rng {
+        pub fn new(seed: u64) -> Self {
+            // the all-zero state never leaves zero
+            Self(if seed == 0 { DEFAULT_SEED } else { seed })
+        }
+
+        pub fn next_u64(&mut self) -> u64 {
+            self.0 ^= self.0 >> 12;
+            self.0 ^= self.0 << 25;
+            self.0 ^= self.0 >> 27;
+            self.0.wrapping_mul(0x2545_f491_4f6c_dd1d)
+        }
+
+        /// uniform-ish in `0..=max`
+        pub fn up_to(&mut self, max: usize) -> usize {
+            (self.next_u64() % (max as u64 + 1)) as usize
+        }
+
+        pub fn fill(&mut self, bytes: &mut [u8]) {
+            for chunk in bytes.chunks_mut(8) {
+                chunk.copy_from_slice(&self.next_u64().to_le_bytes()[..chunk.len()]);
+            }
+        }
+
+        pub fn histogram_size(&mut self, histogram: &[(usize, u32)]) -> usize {
+            let total: u64 = histogram.iter().map(|(_, weight)| *weight as u64).sum();
+            let mut pick = self.next_u64() % total;
+            let mut low = 0;
+            for &(high, weight) in histogram {
+                if pick < weight as u64 {
+                    return low + self.up_to(high - low);
+                }
+                pick -= weight as u64;
+                low = high + 1;
+            }
+            unreachable!()
+        }
+    }
+
+    // fills packet `i` of a batch and returns how much of `buffer` is in use, plus the source of a
+    // captured datagram
+    fn fill<const N: usize>(config: &Config, rng: &mut Rng, i: usize, buffer: &mut [u8; N]) -> (usize, Option<SocketAddr>) {
+        match config.payload {
+            Payload::Zero => (N, None),
+            Payload::Random => {
+                rng.fill(buffer);
+                (N, None)
+            }
+            Payload::ZeroTail => {
+                let size = rng.up_to(N / 4);
+                rng.fill(&mut buffer[..size]);
+                (size, None)
+            }
+            Payload::Histogram => {
+                let size = rng.histogram_size(&config.histogram).min(N);
+                rng.fill(&mut buffer[..size]);
+                (size, None)
+            }
+            Payload::Pcap => {
+                let datagram = &config.capture[i % config.capture.len()];
+                // only `PACKET_DATA_SIZE` is checked up front, smaller `N` get a prefix
+                let size = datagram.payload.len().min(N);
+                buffer[..size].copy_from_slice(&datagram.payload[..size]);
+                (size, Some(datagram.source))
+            }
+        }
+    }
+
+    /// `count` buffers filled according to `config()` and their `Meta`, whose size is `N` unless the
+    /// payload has a zero tail. starts over from the seed (or the first datagram) on every call, so
+    /// every bench sees the same data
+    #[cfg(all(test, feature = "packet-meta"))]
+    pub fn sized_packets<const N: usize, T>(count: usize, mut packet: impl FnMut([u8; N], Meta) -> T) -> Vec<T> {
+        let config = config();
+        let mut rng = Rng::new(config.seed);
+        (0..count)
+            .map(|i| {
+                let mut buffer = [0; N];
+                let meta = match fill(config, &mut rng, i, &mut buffer) {
+                    (size, Some(source)) => Meta {
+                        size,
+                        addr: source.ip(),
+                        port: source.port(),
+                        ..Meta::default()
+                    },
+                    (size, None) => Meta::synthetic(size),
+                };
+                packet(buffer, meta)
+            })
+            .collect()
+    }
+
+    /// same as `sized_packets()` without the rest of `Meta`: how much of each buffer is in use and
+    /// its source, `SYNTHETIC_SOURCE` unless it came from a capture
+    #[cfg_attr(not(feature = "format-bincode"), allow(dead_code))]
+    pub fn sourced_packets<const N: usize, T>(count: usize, mut packet: impl FnMut([u8; N], usize, SocketAddr) -> T) -> Vec<T> {
+        let config = config();
+        let mut rng = Rng::new(config.seed);
+        (0..count)
+            .map(|i| {
+                let mut buffer = [0; N];
+                let (size, source) = fill(config, &mut rng, i, &mut buffer);
+                packet(buffer, size, source.unwrap_or(SYNTHETIC_SOURCE))
+            })
+            .collect()
+    }
+
+    /// same as `sized_packets()` for packets that don't track their size
+    #[cfg_attr(not(test), allow(dead_code))]
+    pub fn packets<const N: usize, T>(count: usize, mut packet: impl FnMut([u8; N]) -> T) -> Vec<T> {
+        let config = config();
+        let mut rng = Rng::new(config.seed);
+        (0..count)
+            .map(|i| {
+                let mut buffer = [0; N];
+                fill(config, &mut rng, i, &mut buffer);
+                packet(buffer)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_histogram() {
+        let histogram = parse_histogram("# size weight\n100 1\n\n200 0\n1232 3\n");
+        assert_eq!(histogram, [(100, 1), (200, 0), (1232, 3)]);
+
+        let mut rng = Rng::new(42);
+        let sizes: Vec<_> = (0..1000).map(|_| rng.histogram_size(&histogram)).collect();
+        assert!(sizes.iter().all(|size| *size <= 100 || (201..=1232).contains(size)));
+        assert!(sizes.iter().any(|size| *size <= 100) && sizes.iter().any(|size| *size > 200));
+
+        let mut again = Rng::new(42);
+        assert_eq!(sizes, (0..1000).map(|_| again.histogram_size(&histogram)).collect::<Vec<_>>());
+    }
+}
+
+// a bincode `Vec<T>` read one element at a time: the length prefix up front, then each `T` on
+// demand, so a batch never has to be in memory all at once
+#[cfg(feature = "format-bincode")]
+#[cfg_attr(not(feature = "strategy-serde-bytes"), allow(dead_code))]
+#[cfg(test)]
+mod packet_stream {
+    use {
+        core::marker::PhantomData,
+        serde::de::DeserializeOwned,
+        std::io::Read,
+    };
+
+    pub struct PacketStream<R, T> {
+        reader: R,
+        remaining: u64,
+        _packet: PhantomData<fn() -> T>,
+    }
+
+    impl<R: Read, T: DeserializeOwned> PacketStream<R, T> {
+        /// reads the length prefix. slices work too, as `&[u8]` is `Read`
+        pub fn new(mut reader: R) -> bincode::Result<Self> {
+            let remaining = bincode::deserialize_from(&mut reader)?;
+            Ok(Self {
+                reader,
+                remaining,
+                _packet: PhantomData,
+            })
+        }
+
+        pub fn remaining(&self) -> u64 {
+            self.remaining
+        }
+
+        /// the reader, positioned after the last element read
+        pub fn into_inner(self) -> R {
+            self.reader
+        }
+    }
+
+    impl<R: Read, T: DeserializeOwned> Iterator for PacketStream<R, T> {
+        type Item = bincode::Result<T>;
+
+        fn next(&mut self) -> Option<Self::Item> {
+            if self.remaining == 0 {
+                return None;
+            }
+            let packet = bincode::deserialize_from(&mut self.reader);
+            // the reader is somewhere in the middle of an element now, nothing after it makes sense
+            self.remaining = if packet.is_ok() { self.remaining - 1 } else { 0 };
+            Some(packet)
+        }
+
+        fn size_hint(&self) -> (usize, Option<usize>) {
+            match usize::try_from(self.remaining) {
+                Ok(remaining) => (remaining, Some(remaining)),
+                Err(_) => (usize::MAX, None),
+            }
+        }
+    }
+}
+
+// a sequence serialized straight from an iterator, without collecting it into a `Vec` first.
+// the length has to be known up front for bincode's prefix, hence `ExactSizeIterator`; one that
+// yields a different number of items than it promised is an error rather than a corrupt batch
+#[cfg_attr(not(all(feature = "format-bincode", feature = "strategy-serde-bytes")), allow(dead_code))]
+#[cfg(test)]
+mod serde_seq_iter {
+    use serde::{
+        ser::{Error, SerializeSeq},
+        Serialize, Serializer,
+    };
+
+    /// `Serialize` for the iterator. it's cloned for every `serialize()`, since e.g.
+    /// `bincode::serialize()` walks it once for the size and once more to write
+    pub struct SeqIter<I>(I);
+
+    impl<I> SeqIter<I> {
+        pub fn new(iter: impl IntoIterator<IntoIter = I>) -> Self {
+            Self(iter.into_iter())
+        }
+    }
+
+    impl<I> Serialize for SeqIter<I>
+    where
+        I: ExactSizeIterator + Clone,
+        I::Item: Serialize,
+    {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            serialize(self.0.clone(), serializer)
+        }
+    }
+
+    #[inline(always)]
+    pub fn serialize<I, S>(iter: I, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        I: IntoIterator,
+        I::IntoIter: ExactSizeIterator,
+        I::Item: Serialize,
+        S: Serializer,
+    {
+        let iter = iter.into_iter();
+        let len = iter.len();
+        let mut seq = serializer.serialize_seq(Some(len))?;
+        let mut count = 0;
+        for item in iter {
+            seq.serialize_element(&item)?;
+            count += 1;
+        }
+        if count != len {
+            return Err(S::Error::custom(format_args!("iterator promised {} items but yielded {}", len, count)));
+        }
+        seq.end()
+    }
+
+    /// `bincode::serialize_into()` of the `Vec` the iterator would collect into, in one pass so
+    /// the iterator needn't be `Clone`
+    #[cfg(feature = "format-bincode")]
+    pub fn serialize_into<W, I>(writer: W, iter: I) -> bincode::Result<()>
+    where
+        W: std::io::Write,
+        I: IntoIterator,
+        I::IntoIter: ExactSizeIterator,
+        I::Item: Serialize,
+    {
+        use bincode::Options;
+
+        // what the `bincode::serialize*()` functions use
+        let options = bincode::DefaultOptions::new().with_fixint_encoding().allow_trailing_bytes();
+        serialize(iter, &mut bincode::Serializer::new(writer, options))
+    }
+}
+
+// a bincode `Vec<Packet>` of `{ buffer: [u8; N], flags: u64 }` with `buffer` as a byte string
+// (serde_bytes_array, FixedBytes, #[fast_array_serde]) has fixed-size records: 8 bytes of length
+// prefix, N bytes of buffer, 8 bytes of flags. so packet i can be read in place without
+// deserializing any of the others
+#[cfg_attr(not(all(feature = "format-bincode", feature = "strategy-serde-bytes")), allow(dead_code))]
+#[cfg(test)]
+mod packet_batch_view {
+    use {
+        crate::encoded_len::{byte_string, EncodedLen},
+        std::io,
+    };
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub struct PacketRef<'a, const N: usize> {
+        pub buffer: &'a [u8; N],
+        pub flags: u64,
+    }
+
+    #[derive(Clone, Copy)]
+    pub struct PacketBatchView<'a, const N: usize> {
+        records: &'a [u8],
+    }
+
+    impl<'a, const N: usize> PacketBatchView<'a, N> {
+        pub const RECORD_LEN: usize = byte_string(N) + u64::ENCODED_LEN;
+
+        /// checks the batch length against the input and every record's buffer length against
+        /// `N` once, so `get()` needn't. like `bincode::deserialize()`, ignores trailing bytes
+        pub fn new(bytes: &'a [u8]) -> io::Result<Self> {
+            let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidData, message);
+
+            let count = bytes.get(..8).ok_or_else(|| invalid("truncated batch length".to_string()))?;
+            let count = u64::from_le_bytes(count.try_into().unwrap());
+            let records_len = usize::try_from(count)
+                .ok()
+                .and_then(|count| count.checked_mul(Self::RECORD_LEN))
+                .filter(|records_len| *records_len <= bytes.len() - 8)
+                .ok_or_else(|| invalid(format!("{} packets don't fit in {} bytes", count, bytes.len())))?;
+            let records = &bytes[8..8 + records_len];
+            for (i, record) in records.chunks_exact(Self::RECORD_LEN).enumerate() {
+                let len = u64::from_le_bytes(record[..8].try_into().unwrap());
+                if len != N as u64 {
+                    return Err(invalid(format!("packet {} has a {} byte buffer, expected {}", i, len, N)));
+                }
+            }
+            Ok(Self { records })
+        }
+
+        #[inline(always)]
+        pub fn len(&self) -> usize {
+            self.records.len() / Self::RECORD_LEN
+        }
+
+        #[inline(always)]
+        pub fn is_empty(&self) -> bool {
+            self.records.is_empty()
+        }
+
+        #[inline(always)]
+        pub fn get(&self, index: usize) -> Option<PacketRef<'a, N>> {
+            if index >= self.len() {
+                return None;
+            }
+            let record = &self.records[index * Self::RECORD_LEN..][..Self::RECORD_LEN];
+            Some(PacketRef {
+                buffer: record[8..8 + N].try_into().unwrap(),
+                flags: u64::from_le_bytes(record[8 + N..].try_into().unwrap()),
+            })
+        }
+
+        pub fn iter(&self) -> impl ExactSizeIterator<Item = PacketRef<'a, N>> + '_ {
+            (0..self.len()).map(|index| self.get(index).unwrap())
+        }
+    }
+}
+
+// bincode size (fixint, as `bincode::serialize()` writes it) of types whose encoding has the same
+// length for every value, as a const. output buffers can then be sized exactly, and record i of a
+// batch starts at `8 + i * T::ENCODED_LEN`, without serializing anything.
+// `[u8; N]` fields depend on the strategy, so packets implement this by hand: tuple encoding
+// (serde's own arrays, serde_as `[_; N]`, serde_arrays) is `N`, byte strings
+// (serde_bytes_array, serde_with::Bytes, FixedBytes) are `byte_string(N)`
+#[cfg(test)]
+mod encoded_len {
+    pub trait EncodedLen {
+        const ENCODED_LEN: usize;
+    }
+
+    macro_rules! impl_encoded_len {
+        ($($ty:ty),*) => {
+            $(
+                impl EncodedLen for $ty {
+                    const ENCODED_LEN: usize = core::mem::size_of::<$ty>();
+                }
+            )*
+        }
+    }
+
+    impl_encoded_len!(bool, u8, u16, u32, u64, u128, i8, i16, i32, i64, i128, f32, f64);
+
+    // serialize_tuple: the elements back to back, no length
+    impl<T: EncodedLen, const N: usize> EncodedLen for [T; N] {
+        const ENCODED_LEN: usize = N * T::ENCODED_LEN;
+    }
+
+    #[cfg(feature = "strategy-serde-bytes")]
+    impl<const N: usize> EncodedLen for crate::fixed_bytes::FixedBytes<N> {
+        const ENCODED_LEN: usize = byte_string(N);
+    }
+
+    /// `len` bytes with bincode's u64 length prefix
+    pub const fn byte_string(len: usize) -> usize {
+        8 + len
+    }
+
+    /// a `Vec<T>` of `count` elements
+    pub const fn batch<T: EncodedLen>(count: usize) -> usize {
+        8 + count * T::ENCODED_LEN
+    }
+}
+
+// `Vec<T>` deserialized over an existing one: the allocation is kept and the elements already
+// there are overwritten where they are, so a batch of big packets costs no allocation (and no
+// fresh pages) per decode. serde's own `deserialize_in_place` is hidden and derive skips it for
+// `with` fields, so element types spell it out with `DeserializeInPlace`
+#[cfg_attr(not(all(feature = "format-bincode", feature = "strategy-serde-bytes")), allow(dead_code))]
+#[cfg(test)]
+mod serde_vec_in_place {
+    use {
+        core::fmt,
+        serde::de::{Deserialize, DeserializeSeed, Deserializer, SeqAccess, Visitor},
+    };
+
+    pub trait DeserializeInPlace<'de>: Deserialize<'de> {
+        /// on error `place` may be partly overwritten
+        fn deserialize_into<D>(deserializer: D, place: &mut Self) -> Result<(), D::Error>
+        where
+            D: Deserializer<'de>;
+    }
+
+    const MAX_PREALLOC_BYTES: usize = 1024 * 1024;
+
+    /// seed that deserializes a sequence into the `Vec`: elements past the old length are pushed,
+    /// surplus ones truncated. on error the `Vec` holds a mix of old and new elements
+    pub struct VecInPlace<'a, T>(pub &'a mut Vec<T>);
+
+    impl<'de, T: DeserializeInPlace<'de>> DeserializeSeed<'de> for VecInPlace<'_, T> {
+        type Value = ();
+
+        #[inline(always)]
+        fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            deserializer.deserialize_seq(self)
+        }
+    }
+
+    impl<'de, T: DeserializeInPlace<'de>> Visitor<'de> for VecInPlace<'_, T> {
+        type Value = ();
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a sequence")
+        }
+
+        fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+        where
+            A: SeqAccess<'de>,
+        {
+            let vec = self.0;
+            for filled in 0..vec.len() {
+                if seq.next_element_seed(InPlace(&mut vec[filled]))?.is_none() {
+                    vec.truncate(filled);
+                    return Ok(());
+                }
+            }
+            // the hint is whatever length the input claims: up to 1 MiB up front, like serde's own
+            // `Vec` impl, and the rest as the elements actually arrive
+            vec.reserve(seq.size_hint().unwrap_or(0).min(MAX_PREALLOC_BYTES / core::mem::size_of::<T>().max(1)));
+            while let Some(element) = seq.next_element()? {
+                vec.push(element);
+            }
+            Ok(())
+        }
+    }
+
+    /// `DeserializeInPlace` as a seed, for visitors of types containing `T`
+    pub struct InPlace<'a, T>(pub &'a mut T);
+
+    impl<'de, T: DeserializeInPlace<'de>> DeserializeSeed<'de> for InPlace<'_, T> {
+        type Value = ();
+
+        #[inline(always)]
+        fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            T::deserialize_into(deserializer, self.0)
+        }
+    }
+
+    /// `bincode::deserialize()` into `vec`
+    #[cfg(feature = "format-bincode")]
+    pub fn deserialize_into_vec<'de, T: DeserializeInPlace<'de>>(bytes: &'de [u8], vec: &mut Vec<T>) -> bincode::Result<()> {
+        use bincode::Options;
+
+        // what the `bincode::deserialize*()` functions use
+        let options = bincode::DefaultOptions::new().with_fixint_encoding().allow_trailing_bytes();
+        options.deserialize_seed(VecInPlace(vec), bytes)
+    }
+}
+
+#[cfg(feature = "format-bincode")]
+#[bench]
+fn bench_serialize_vanilla(bencher: &mut test::Bencher) {
+    #[derive(Default, Clone, Serialize)]
+    pub struct Packet {
+        buffer: [u8; 32],
+        flags: u64,
+    }
+
+    let mut output_binary = vec![];
+    let input_packets: Vec<_> = payload::packets(32, |buffer| Packet { buffer, ..Packet::default() });
+
+    bencher.iter(|| {
+        bincode::serialize_into(&mut output_binary, &input_packets).unwrap();
+        test::black_box(&output_binary);
+        output_binary.clear();
+    })
+}
+
+#[cfg(all(feature = "format-bincode", feature = "strategy-serde-with"))]
+#[bench]
+fn bench_serialize_serde_as(bencher: &mut test::Bencher) {
+    use serde_with::serde_as;
+
+    #[serde_as]
+    #[derive(Default, Clone, Serialize)]
+    pub struct Packet {
+        #[serde_as(as = "[_; 32]")]
+        buffer: [u8; 32],
+        flags: u64,
+    }
+
+    let mut output_binary = vec![];
+    let input_packets: Vec<_> = payload::packets(32, |buffer| Packet { buffer, ..Packet::default() });
+
+    bencher.iter(|| {
+        bincode::serialize_into(&mut output_binary, &input_packets).unwrap();
+        test::black_box(&output_binary);
+        output_binary.clear();
+    })
+}
+
+#[cfg(all(feature = "format-bincode", feature = "strategy-serde-arrays"))]
+#[bench]
+fn bench_serialize_serde_arrays(bencher: &mut test::Bencher) {
+    #[derive(Default, Clone, Serialize)]
+    pub struct Packet {
+        #[serde(with = "serde_arrays")]
+        buffer: [u8; 32],
+        flags: u64,
+    }
+
+    let mut output_binary = vec![];
+    let input_packets: Vec<_> = payload::packets(32, |buffer| Packet { buffer, ..Packet::default() });
+
+    bencher.iter(|| {
+        bincode::serialize_into(&mut output_binary, &input_packets).unwrap();
+        test::black_box(&output_binary);
+        output_binary.clear();
+    })
+}
+
+#[cfg(all(feature = "format-bincode", feature = "strategy-serde-bytes"))]
+#[bench]
+fn bench_serialize_serde_bytes(bencher: &mut test::Bencher) {
+    mod serde_bytes_array {
+        use serde::Serializer;
+
+        pub(crate) fn serialize<S>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            serde_bytes::serialize(bytes, serializer)
+        }
+    }
+
+    #[derive(Default, Clone, Serialize)]
+    pub struct Packet {
+        #[serde(with = "serde_bytes_array")]
+        buffer: [u8; 32],
+        flags: u64,
+    }
+
+    let mut output_binary = vec![];
+    let input_packets: Vec<_> = payload::packets(32, |buffer| Packet { buffer, ..Packet::default() });
+
+    bencher.iter(|| {
+        bincode::serialize_into(&mut output_binary, &input_packets).unwrap();
+        test::black_box(&output_binary);
+        output_binary.clear();
+    })
+}
+
+#[cfg(all(feature = "format-bincode", feature = "strategy-serde-arrays"))]
+#[bench]
+fn bench_serialize_serde_arrays_normal(bencher: &mut test::Bencher) {
+    #[derive(Clone, Serialize)]
+    pub struct Packet {
+        #[serde(with = "serde_arrays")]
+        buffer: [u8; PACKET_DATA_SIZE],
+        flags: u64,
+    }
+    impl Default for Packet {
+        fn default() -> Self {
+            Self {
+                buffer: [0; PACKET_DATA_SIZE],
+                flags: 3,
+            }
+        }
+    }
+
+    let mut output_binary = vec![];
+    let input_packets: Vec<_> = payload::packets(512, |buffer| Packet { buffer, ..Packet::default() });
+
+    bencher.iter(|| {
+        bincode::serialize_into(&mut output_binary, &input_packets).unwrap();
+        test::black_box(&output_binary);
+        output_binary.clear();
+    })
+}
+
+#[cfg(all(feature = "format-bincode", feature = "strategy-serde-with"))]
+#[bench]
+fn bench_serialize_serde_as_normal(bencher: &mut test::Bencher) {
+    use serde_with::serde_as;
+
+    #[serde_as]
+    #[derive(Clone, Serialize)]
+    pub struct Packet {
+        #[serde_as(as = "[_; PACKET_DATA_SIZE]")]
+        buffer: [u8; PACKET_DATA_SIZE],
+        flags: u64,
+    }
+    impl Default for Packet {
+        fn default() -> Self {
+            Self {
+                buffer: [0; PACKET_DATA_SIZE],
+                flags: 3,
+            }
+        }
+    }
+
+    let mut output_binary = vec![];
+    let input_packets: Vec<_> = payload::packets(512, |buffer| Packet { buffer, ..Packet::default() });
+
+    bencher.iter(|| {
+        bincode::serialize_into(&mut output_binary, &input_packets).unwrap();
+        test::black_box(&output_binary);
+        output_binary.clear();
+    })
+}
+
+#[cfg(feature = "strategy-serde-with")]
+#[cfg(test)]
+mod serde_as_bytes {
+    use super::*;
+
+    use serde_with::{serde_as, Bytes};
+
+    #[serde_as]
+    #[derive(Clone, Serialize, Deserialize)]
+    pub struct Packet {
+        #[serde_as(as = "Bytes")]
+        pub(crate) buffer: [u8; PACKET_DATA_SIZE],
+        pub(crate) flags: u64,
+    }
+    impl Default for Packet {
+        fn default() -> Self {
+            Self {
+                buffer: [0; PACKET_DATA_SIZE],
+                flags: 3,
+            }
+        }
+    }
+
+    #[cfg(feature = "format-bincode")]
+    #[bench]
+    fn bench_serialize_serde_as_bytes_normal(bencher: &mut test::Bencher) {
+        let mut output_binary = vec![];
+        let input_packets: Vec<_> = payload::packets(512, |buffer| Packet { buffer, ..Packet::default() });
+
+        bencher.iter(|| {
+            bincode::serialize_into(&mut output_binary, &input_packets).unwrap();
+            test::black_box(&output_binary);
+            output_binary.clear();
+        })
+    }
+
+    #[cfg(feature = "format-bincode")]
+    #[bench]
+    fn bench_deserialize_serde_bytes_normal(bencher: &mut test::Bencher) {
+        let input_packets: Vec<_> = payload::packets(512, |buffer| Packet { buffer, ..Packet::default() });
+        let s = bincode::serialize(&input_packets).unwrap();
+
+        bencher.iter(|| {
+            test::black_box(bincode::deserialize::<Vec<Packet>>(&s).unwrap());
+        })
+    }
+
+    #[cfg(feature = "format-bincode")]
+    #[bench]
+    fn bench_deserialize_from_serde_bytes_normal(bencher: &mut test::Bencher) {
+        let input_packets: Vec<_> = payload::packets(512, |buffer| Packet { buffer, ..Packet::default() });
+        let s = bincode::serialize(&input_packets).unwrap();
+
+        bencher.iter(|| {
+            let mut reader = &s[..];
+            bincode::deserialize_from::<_, Vec<Packet>>(&mut reader).unwrap();
+        })
+    }
+}
+
+
+#[cfg(feature = "strategy-serde-bytes")]
+#[cfg(test)]
+mod serde_bytes_vec {
+    use super::*;
+
+    mod serde_bytes_array {
+        use {
+            core::convert::TryInto,
+            serde::{de::Error, Deserializer, Serializer},
+        };
+
+        #[inline(always)]
+        pub(crate) fn serialize<S>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            serde_bytes::serialize(bytes, serializer)
+        }
+
+        #[inline(always)]
+        pub(crate) fn deserialize<'de, D, const N: usize>(deserializer: D) -> Result<[u8; N], D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            let vec: Vec<u8> = serde_bytes::deserialize(deserializer)?;
+            let vec_len = vec.len();
+            let array: [u8; N] = vec.try_into().map_err(|_| {
+                let expected = format!("[u8; {}]", N);
+                D::Error::invalid_length(vec_len, &expected.as_str())
+            })?;
+            Ok(array)
+        }
+    }
+
+    #[derive(Clone, Serialize, Deserialize, Debug)]
+    pub struct Packet {
+        #[serde(with = "serde_bytes_array")]
+        pub(crate) buffer: [u8; PACKET_DATA_SIZE],
+        pub(crate) flags: u64,
+    }
+    impl Default for Packet {
+        #[inline(always)]
+        fn default() -> Self {
+            Self {
+                buffer: [0; PACKET_DATA_SIZE],
+                flags: 3,
+            }
+        }
+    }
+
+    #[cfg(feature = "format-bincode")]
+    #[bench]
+    fn bench_serialize_serde_bytes_normal(bencher: &mut test::Bencher) {
+        let mut output_binary = vec![];
+        let input_packets: Vec<_> = payload::packets(512, |buffer| Packet { buffer, ..Packet::default() });
+
+        bencher.iter(|| {
+            bincode::serialize_into(&mut output_binary, &input_packets).unwrap();
+            test::black_box(&output_binary);
+            output_binary.clear();
+        })
+    }
+
+    #[cfg(feature = "format-bincode")]
+    #[bench]
+    fn bench_deserialize_serde_bytes_normal(bencher: &mut test::Bencher) {
+        let input_packets: Vec<_> = payload::packets(512, |buffer| Packet { buffer, ..Packet::default() });
+        let s = bincode::serialize(&input_packets).unwrap();
+
+        bencher.iter(|| {
+            test::black_box(bincode::deserialize::<Vec<Packet>>(&s).unwrap());
+        })
+    }
+
+    #[cfg(feature = "format-bincode")]
+    #[bench]
+    fn bench_deserialize_from_serde_bytes_normal(bencher: &mut test::Bencher) {
+        let input_packets: Vec<_> = payload::packets(512, |buffer| Packet { buffer, ..Packet::default() });
+        let s = bincode::serialize(&input_packets).unwrap();
+
+        bencher.iter(|| {
+            let mut reader = &s[..];
+            bincode::deserialize_from::<_, Vec<Packet>>(&mut reader).unwrap();
+        })
+    }
+}
+
+#[cfg(feature = "strategy-serde-bytes")]
+#[cfg(test)]
+mod serde_bytes_cow {
+    use super::*;
+
+    mod serde_bytes_array {
+        use {
+            core::convert::TryInto,
+            serde::{de::Error, Deserializer, Serializer},
+        };
+
+        #[inline(always)]
+        pub(crate) fn serialize<S>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            serde_bytes::serialize(bytes, serializer)
+        }
+
+        #[inline(always)]
+        pub(crate) fn deserialize<'de, D, const N: usize>(deserializer: D) -> Result<[u8; N], D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            let slice: std::borrow::Cow<'de, [u8]> = serde_bytes::deserialize(deserializer)?;
+            //dbg!(&slice);
+            let array: [u8; N] = (&*slice).try_into().map_err(|_| {
+                let expected = format!("[u8; {}]", N);
+                D::Error::invalid_length(slice.len(), &expected.as_str())
+            })?;
+            Ok(array)
+        }
+    }
+
+    #[derive(Clone, Serialize, Deserialize, Debug)]
+    pub struct Packet {
+        #[serde(with = "serde_bytes_array")]
+        pub(crate) buffer: [u8; PACKET_DATA_SIZE],
+        pub(crate) flags: u64,
+    }
+    impl Default for Packet {
+        #[inline(always)]
+        fn default() -> Self {
+            Self {
+                buffer: [0; PACKET_DATA_SIZE],
+                flags: 3,
+            }
+        }
+    }
+
+    #[cfg(feature = "format-bincode")]
+    #[bench]
+    fn bench_serialize_serde_bytes_normal(bencher: &mut test::Bencher) {
+        let mut output_binary = vec![];
+        let input_packets: Vec<_> = payload::packets(512, |buffer| Packet { buffer, ..Packet::default() });
+
+        bencher.iter(|| {
+            bincode::serialize_into(&mut output_binary, &input_packets).unwrap();
+            test::black_box(&output_binary);
+            output_binary.clear();
+        })
+    }
+
+    #[cfg(feature = "format-bincode")]
+    #[bench]
+    fn bench_deserialize_serde_bytes_normal(bencher: &mut test::Bencher) {
+        let input_packets: Vec<_> = payload::packets(512, |buffer| Packet { buffer, ..Packet::default() });
+        let s = bincode::serialize(&input_packets).unwrap();
+
+        bencher.iter(|| {
+            //let mut reader = &s[..];
+            //bincode::deserialize_from::<_, Vec<Packet>>(&mut stream).unwrap();
+            //test::black_box(bincode::deserialize_from::<_, Vec<Packet>>(&mut stream).unwrap());
+            //bincode::deserialize_from::<_, Vec<Packet>>(&mut stream).unwrap();
+            test::black_box(bincode::deserialize::<Vec<Packet>>(&s).unwrap());
+            //bincode::deserialize::<Vec<Packet>>(&s).unwrap();
+        })
+    }
+
+    #[cfg(feature = "format-bincode")]
+    #[bench]
+    fn bench_deserialize_from_serde_bytes_normal(bencher: &mut test::Bencher) {
+        let input_packets: Vec<_> = payload::packets(512, |buffer| Packet { buffer, ..Packet::default() });
+        let s = bincode::serialize(&input_packets).unwrap();
+
+        bencher.iter(|| {
+            let mut reader = &s[..];
+            bincode::deserialize_from::<_, Vec<Packet>>(&mut reader).unwrap();
+        })
+    }
+}
+
+#[cfg(feature = "strategy-serde-bytes")]
+#[cfg(test)]
+mod serde_bytes_slice {
+    use super::*;
+
+    mod serde_bytes_array {
+        use {
+            core::{convert::TryInto, fmt},
+            serde::{
+                de::{Error, Visitor},
+                Deserializer, Serializer,
+            },
+        };
+
+        #[inline(always)]
+        pub(crate) fn serialize<S>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            serde_bytes::serialize(bytes, serializer)
+        }
+
+        #[inline(always)]
+        pub(crate) fn deserialize<'de, D, const N: usize>(deserializer: D) -> Result<[u8; N], D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            deserializer.deserialize_bytes(SliceVisitor)
+        }
+
+        // the slice as the deserializer hands it out: borrowed from the input when it can, a
+        // transient buffer when reading from a stream, where a `&'de [u8]` can't exist
+        struct SliceVisitor<const N: usize>;
+
+        impl<'de, const N: usize> Visitor<'de> for SliceVisitor<N> {
+            type Value = [u8; N];
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                write!(formatter, "[u8; {}] as bytes", N)
+            }
+
+            #[inline(always)]
+            fn visit_bytes<E>(self, slice: &[u8]) -> Result<Self::Value, E>
+            where
+                E: Error,
+            {
+                slice.try_into().map_err(|_| E::invalid_length(slice.len(), &self))
+            }
+        }
+    }
+
+    #[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+    pub struct Packet {
+        #[serde(with = "serde_bytes_array")]
+        pub(crate) buffer: [u8; PACKET_DATA_SIZE],
+        pub(crate) flags: u64,
+    }
+    impl Default for Packet {
+        #[inline(always)]
+        fn default() -> Self {
+            Self {
+                buffer: [0; PACKET_DATA_SIZE],
+                flags: 3,
+            }
+        }
+    }
+
+    #[cfg(feature = "format-bincode")]
+    #[bench]
+    fn bench_serialize_serde_bytes_normal(bencher: &mut test::Bencher) {
+        let mut output_binary = vec![];
+        let input_packets: Vec<_> = payload::packets(512, |buffer| Packet { buffer, ..Packet::default() });
+
+        bencher.iter(|| {
+            bincode::serialize_into(&mut output_binary, &input_packets).unwrap();
+            test::black_box(&output_binary);
+            output_binary.clear();
+        })
+    }
+
+    #[cfg(feature = "format-bincode")]
+    #[bench]
+    fn bench_deserialize_serde_bytes_normal(bencher: &mut test::Bencher) {
+        let input_packets: Vec<_> = payload::packets(512, |buffer| Packet { buffer, ..Packet::default() });
+        let s = bincode::serialize(&input_packets).unwrap();
+
+        bencher.iter(|| {
+            test::black_box(bincode::deserialize::<Vec<Packet>>(&s).unwrap());
+        })
+    }
+
+    #[cfg(feature = "format-bincode")]
+    #[bench]
+    fn bench_deserialize_from_serde_bytes_normal(bencher: &mut test::Bencher) {
+        let input_packets: Vec<_> = payload::packets(512, |buffer| Packet { buffer, ..Packet::default() });
+        let s = bincode::serialize(&input_packets).unwrap();
+
+        bencher.iter(|| {
+            let mut reader = &s[..];
+            bincode::deserialize_from::<_, Vec<Packet>>(&mut reader).unwrap();
+        })
+    }
+
+    // counterpart of rkyv_archived::bench_access_*: deserialize, then touch buffer and flags
+    #[cfg(feature = "format-bincode")]
+    #[bench]
+    fn bench_access_serde_bytes_normal(bencher: &mut test::Bencher) {
+        let input_packets = payload::packets(512, |buffer| Packet { buffer, ..Packet::default() });
+        let s = bincode::serialize(&input_packets).unwrap();
+
+        bencher.iter(|| {
+            let packets = bincode::deserialize::<Vec<Packet>>(&s).unwrap();
+            test::black_box(packets.iter().map(|p| p.buffer[0] as u64 + p.flags).sum::<u64>());
+        })
+    }
+}
+
+#[cfg(all(feature = "format-json", feature = "strategy-serde-bytes"))]
+#[cfg(test)]
+mod serde_bytes_slice_json {
+    use super::*;
+
+    #[cfg(feature = "strategy-serde-with")]
+    use serde_with::serde_as;
+
+    // deliberately not crate::serde_bytes_array: the *_serde_bytes_normal benches measure plain
+    // serde_bytes output (a JSON array of numbers), the hex/base64 variants have their own benches
+    mod serde_bytes_array {
+        use {
+            core::{convert::TryInto, fmt},
+            serde::{
+                de::{Error, SeqAccess, Visitor},
+                Deserializer, Serializer,
+            },
+        };
+
+        #[inline(always)]
+        pub(crate) fn serialize<S>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            serde_bytes::serialize(bytes, serializer)
+        }
+
+        #[inline(always)]
+        pub(crate) fn deserialize<'de, D, const N: usize>(deserializer: D) -> Result<[u8; N], D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            deserializer.deserialize_bytes(SliceVisitor)
+        }
+
+        // JSON has no byte strings: serde_bytes writes an array of numbers, which can't be
+        // borrowed, so that is read back element by element
+        struct SliceVisitor<const N: usize>;
+
+        impl<'de, const N: usize> Visitor<'de> for SliceVisitor<N> {
+            type Value = [u8; N];
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                write!(formatter, "[u8; {}] as bytes or a sequence", N)
+            }
+
+            #[inline(always)]
+            fn visit_bytes<E>(self, slice: &[u8]) -> Result<Self::Value, E>
+            where
+                E: Error,
+            {
+                slice.try_into().map_err(|_| E::invalid_length(slice.len(), &self))
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                let mut array = [0; N];
+                for (i, byte) in array.iter_mut().enumerate() {
+                    *byte = seq.next_element()?.ok_or_else(|| A::Error::invalid_length(i, &self))?;
+                }
+                if seq.next_element::<u8>()?.is_some() {
+                    return Err(A::Error::invalid_length(N + 1, &self));
+                }
+                Ok(array)
+            }
+        }
+    }
+
+    #[derive(Clone, Serialize, Deserialize, Debug)]
+    pub struct Packet {
+        #[serde(with = "serde_bytes_array")]
+        buffer: [u8; PACKET_DATA_SIZE],
+        flags: u64,
+    }
+    impl Default for Packet {
+        #[inline(always)]
+        fn default() -> Self {
+            Self {
+                buffer: [0; PACKET_DATA_SIZE],
+                flags: 3,
+            }
+        }
+    }
+
+    #[cfg(feature = "strategy-serde-with")]
+    #[serde_as]
+    #[derive(Clone, Serialize)]
+    pub struct SerdeAsArrayPacket {
+        #[serde_as(as = "[_; PACKET_DATA_SIZE]")]
+        buffer: [u8; PACKET_DATA_SIZE],
+        flags: u64,
+    }
+    #[cfg(feature = "strategy-serde-with")]
+    impl Default for SerdeAsArrayPacket {
+        #[inline(always)]
+        fn default() -> Self {
+            Self {
+                buffer: [0; PACKET_DATA_SIZE],
+                flags: 3,
+            }
+        }
+    }
+
+    #[cfg(feature = "strategy-serde-arrays")]
+    #[derive(Clone, Serialize)]
+    pub struct SerdeArraysPacket {
+        #[serde(with = "serde_arrays")]
+        buffer: [u8; PACKET_DATA_SIZE],
+        flags: u64,
+    }
+    #[cfg(feature = "strategy-serde-arrays")]
+    impl Default for SerdeArraysPacket {
+        #[inline(always)]
+        fn default() -> Self {
+            Self {
+                buffer: [0; PACKET_DATA_SIZE],
+                flags: 3,
+            }
+        }
+    }
+
+    fn bench_to_writer<T: serde::Serialize>(bencher: &mut test::Bencher, input_packets: &[T]) {
+        let mut output_json = vec![];
+
+        bencher.iter(|| {
+            serde_json::to_writer(&mut output_json, input_packets).unwrap();
+            test::black_box(&output_json);
+            output_json.clear();
+        })
+    }
+
+    fn bench_to_writer_pretty<T: serde::Serialize>(bencher: &mut test::Bencher, input_packets: &[T]) {
+        let mut output_json = vec![];
+
+        bencher.iter(|| {
+            serde_json::to_writer_pretty(&mut output_json, input_packets).unwrap();
+            test::black_box(&output_json);
+            output_json.clear();
+        })
+    }
+
+    fn bench_to_vec<T: serde::Serialize>(bencher: &mut test::Bencher, input_packets: &[T]) {
+        bencher.iter(|| {
+            test::black_box(serde_json::to_vec(input_packets).unwrap());
+        })
+    }
+
+    #[bench]
+    fn bench_serialize_to_writer_serde_bytes_normal(bencher: &mut test::Bencher) {
+        bench_to_writer(bencher, &payload::packets(512, |buffer| Packet { buffer, ..Packet::default() }))
+    }
+
+    #[bench]
+    fn bench_serialize_to_writer_pretty_serde_bytes_normal(bencher: &mut test::Bencher) {
+        bench_to_writer_pretty(bencher, &payload::packets(512, |buffer| Packet { buffer, ..Packet::default() }))
+    }
+
+    #[bench]
+    fn bench_serialize_to_vec_serde_bytes_normal(bencher: &mut test::Bencher) {
+        bench_to_vec(bencher, &payload::packets(512, |buffer| Packet { buffer, ..Packet::default() }))
+    }
+
+    #[bench]
+    fn bench_serialize_to_writer_serde_bytes_vec_normal(bencher: &mut test::Bencher) {
+        bench_to_writer(bencher, &payload::packets(512, |buffer| super::serde_bytes_vec::Packet { buffer, flags: 3 }))
+    }
+
+    #[bench]
+    fn bench_serialize_to_writer_pretty_serde_bytes_vec_normal(bencher: &mut test::Bencher) {
+        bench_to_writer_pretty(bencher, &payload::packets(512, |buffer| super::serde_bytes_vec::Packet { buffer, flags: 3 }))
+    }
+
+    #[bench]
+    fn bench_serialize_to_vec_serde_bytes_vec_normal(bencher: &mut test::Bencher) {
+        bench_to_vec(bencher, &payload::packets(512, |buffer| super::serde_bytes_vec::Packet { buffer, flags: 3 }))
+    }
+
+    #[bench]
+    fn bench_serialize_to_writer_serde_bytes_cow_normal(bencher: &mut test::Bencher) {
+        bench_to_writer(bencher, &payload::packets(512, |buffer| super::serde_bytes_cow::Packet { buffer, flags: 3 }))
+    }
+
+    #[bench]
+    fn bench_serialize_to_writer_pretty_serde_bytes_cow_normal(bencher: &mut test::Bencher) {
+        bench_to_writer_pretty(bencher, &payload::packets(512, |buffer| super::serde_bytes_cow::Packet { buffer, flags: 3 }))
+    }
+
+    #[bench]
+    fn bench_serialize_to_vec_serde_bytes_cow_normal(bencher: &mut test::Bencher) {
+        bench_to_vec(bencher, &payload::packets(512, |buffer| super::serde_bytes_cow::Packet { buffer, flags: 3 }))
+    }
+
+    #[cfg(feature = "strategy-serde-with")]
+    #[bench]
+    fn bench_serialize_to_writer_serde_as_bytes_normal(bencher: &mut test::Bencher) {
+        bench_to_writer(bencher, &payload::packets(512, |buffer| super::serde_as_bytes::Packet { buffer, flags: 3 }))
+    }
+
+    #[cfg(feature = "strategy-serde-with")]
+    #[bench]
+    fn bench_serialize_to_writer_pretty_serde_as_bytes_normal(bencher: &mut test::Bencher) {
+        bench_to_writer_pretty(bencher, &payload::packets(512, |buffer| super::serde_as_bytes::Packet { buffer, flags: 3 }))
+    }
+
+    #[cfg(feature = "strategy-serde-with")]
+    #[bench]
+    fn bench_serialize_to_vec_serde_as_bytes_normal(bencher: &mut test::Bencher) {
+        bench_to_vec(bencher, &payload::packets(512, |buffer| super::serde_as_bytes::Packet { buffer, flags: 3 }))
+    }
+
+    #[cfg(feature = "strategy-serde-with")]
+    #[bench]
+    fn bench_serialize_to_writer_serde_as_array_normal(bencher: &mut test::Bencher) {
+        bench_to_writer(bencher, &payload::packets(512, |buffer| SerdeAsArrayPacket { buffer, ..SerdeAsArrayPacket::default() }))
+    }
+
+    #[cfg(feature = "strategy-serde-with")]
+    #[bench]
+    fn bench_serialize_to_writer_pretty_serde_as_array_normal(bencher: &mut test::Bencher) {
+        bench_to_writer_pretty(bencher, &payload::packets(512, |buffer| SerdeAsArrayPacket { buffer, ..SerdeAsArrayPacket::default() }))
+    }
+
+    #[cfg(feature = "strategy-serde-with")]
+    #[bench]
+    fn bench_serialize_to_vec_serde_as_array_normal(bencher: &mut test::Bencher) {
+        bench_to_vec(bencher, &payload::packets(512, |buffer| SerdeAsArrayPacket { buffer, ..SerdeAsArrayPacket::default() }))
+    }
+
+    #[cfg(feature = "strategy-serde-arrays")]
+    #[bench]
+    fn bench_serialize_to_writer_serde_arrays_normal(bencher: &mut test::Bencher) {
+        bench_to_writer(bencher, &payload::packets(512, |buffer| SerdeArraysPacket { buffer, ..SerdeArraysPacket::default() }))
+    }
+
+    #[cfg(feature = "strategy-serde-arrays")]
+    #[bench]
+    fn bench_serialize_to_writer_pretty_serde_arrays_normal(bencher: &mut test::Bencher) {
+        bench_to_writer_pretty(bencher, &payload::packets(512, |buffer| SerdeArraysPacket { buffer, ..SerdeArraysPacket::default() }))
+    }
+
+    #[cfg(feature = "strategy-serde-arrays")]
+    #[bench]
+    fn bench_serialize_to_vec_serde_arrays_normal(bencher: &mut test::Bencher) {
+        bench_to_vec(bencher, &payload::packets(512, |buffer| SerdeArraysPacket { buffer, ..SerdeArraysPacket::default() }))
+    }
+
+    #[bench]
+    fn bench_serialize_to_writer_hex_normal(bencher: &mut test::Bencher) {
+        bench_to_writer(bencher, &payload::packets(512, |buffer| super::serde_bytes_human_readable::Packet { buffer, flags: 3 }))
+    }
+
+    #[bench]
+    fn bench_serialize_to_writer_pretty_hex_normal(bencher: &mut test::Bencher) {
+        bench_to_writer_pretty(bencher, &payload::packets(512, |buffer| super::serde_bytes_human_readable::Packet { buffer, flags: 3 }))
+    }
+
+    #[bench]
+    fn bench_serialize_to_vec_hex_normal(bencher: &mut test::Bencher) {
+        bench_to_vec(bencher, &payload::packets(512, |buffer| super::serde_bytes_human_readable::Packet { buffer, flags: 3 }))
+    }
+
+    #[bench]
+    fn bench_serialize_to_writer_base64_normal(bencher: &mut test::Bencher) {
+        bench_to_writer(bencher, &payload::packets(512, |buffer| super::serde_bytes_human_readable::Base64Packet { buffer, flags: 3 }))
+    }
+
+    #[bench]
+    fn bench_serialize_to_writer_pretty_base64_normal(bencher: &mut test::Bencher) {
+        bench_to_writer_pretty(bencher, &payload::packets(512, |buffer| super::serde_bytes_human_readable::Base64Packet { buffer, flags: 3 }))
+    }
+
+    #[bench]
+    fn bench_serialize_to_vec_base64_normal(bencher: &mut test::Bencher) {
+        bench_to_vec(bencher, &payload::packets(512, |buffer| super::serde_bytes_human_readable::Base64Packet { buffer, flags: 3 }))
+    }
+
+    #[bench]
+    fn bench_deserialize_serde_bytes_normal(bencher: &mut test::Bencher) {
+        let input_packets: Vec<_> = payload::packets(512, |buffer| Packet { buffer, ..Packet::default() });
+        let s = serde_json::to_vec(&input_packets).unwrap();
+
+        bencher.iter(|| {
+            test::black_box(serde_json::from_slice::<Vec<Packet>>(&s).unwrap());
+        })
+    }
+
+    #[bench]
+    fn bench_deserialize_from_serde_bytes_normal(bencher: &mut test::Bencher) {
+        let input_packets: Vec<_> = payload::packets(512, |buffer| Packet { buffer, ..Packet::default() });
+        let s = serde_json::to_vec(&input_packets).unwrap();
+
+        bencher.iter(|| {
+            let mut reader = &s[..];
+            serde_json::from_reader::<_, Vec<Packet>>(&mut reader).unwrap();
+        })
+    }
+}
+
+#[cfg(feature = "strategy-serde-bytes")]
+#[cfg(test)]
+mod serde_bytes_human_readable {
+    use super::*;
+
+    #[cfg(feature = "strategy-serde-with")]
+    use serde_with::serde_as;
+
+    #[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+    pub struct Packet {
+        #[serde(with = "serde_bytes_array")]
+        pub(crate) buffer: [u8; PACKET_DATA_SIZE],
+        pub(crate) flags: u64,
+    }
+    impl Default for Packet {
+        #[inline(always)]
+        fn default() -> Self {
+            Self {
+                buffer: [0; PACKET_DATA_SIZE],
+                flags: 3,
+            }
+        }
+    }
+
+    #[cfg_attr(not(feature = "format-json"), allow(dead_code))]
+    #[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+    pub struct Base64Packet {
+        #[serde(with = "serde_bytes_array::base64")]
+        pub(crate) buffer: [u8; PACKET_DATA_SIZE],
+        pub(crate) flags: u64,
+    }
+    impl Default for Base64Packet {
+        #[inline(always)]
+        fn default() -> Self {
+            Self {
+                buffer: [0; PACKET_DATA_SIZE],
+                flags: 3,
+            }
+        }
+    }
+
+    // the number-array representation to compare against
+    #[cfg(feature = "strategy-serde-with")]
+    #[serde_as]
+    #[cfg_attr(not(feature = "format-json"), allow(dead_code))]
+    #[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+    pub struct NumberArrayPacket {
+        #[serde_as(as = "[_; PACKET_DATA_SIZE]")]
+        pub(crate) buffer: [u8; PACKET_DATA_SIZE],
+        pub(crate) flags: u64,
+    }
+    #[cfg(feature = "strategy-serde-with")]
+    impl Default for NumberArrayPacket {
+        #[inline(always)]
+        fn default() -> Self {
+            Self {
+                buffer: [0; PACKET_DATA_SIZE],
+                flags: 3,
+            }
+        }
+    }
+
+    #[cfg(all(feature = "format-json", feature = "format-bincode", feature = "strategy-serde-with"))]
+    #[test]
+    fn test_deserialize_accepts_every_form() {
+        let packet = Packet {
+            buffer: [0xab; PACKET_DATA_SIZE],
+            flags: 3,
+        };
+        let base64_packet = Base64Packet {
+            buffer: packet.buffer,
+            flags: 3,
+        };
+        let number_array_packet = NumberArrayPacket {
+            buffer: packet.buffer,
+            flags: 3,
+        };
+
+        let hex = serde_json::to_string(&packet).unwrap();
+        assert!(hex.contains(&"ab".repeat(PACKET_DATA_SIZE)));
+        let base64 = serde_json::to_string(&base64_packet).unwrap();
+        let number_array = serde_json::to_string(&number_array_packet).unwrap();
+        for json in [&hex, &base64, &number_array] {
+            assert_eq!(serde_json::from_str::<Packet>(json).unwrap(), packet);
+            assert_eq!(serde_json::from_str::<Base64Packet>(json).unwrap(), base64_packet);
+        }
+
+        // bincode keeps the raw bytes: 8-byte length prefix + buffer + flags
+        let binary = bincode::serialize(&packet).unwrap();
+        assert_eq!(binary.len(), 8 + PACKET_DATA_SIZE + 8);
+        assert_eq!(binary, bincode::serialize(&base64_packet).unwrap());
+        assert_eq!(bincode::deserialize::<Packet>(&binary).unwrap(), packet);
+        let mut reader = &binary[..];
+        assert_eq!(bincode::deserialize_from::<_, Packet>(&mut reader).unwrap(), packet);
+
+        let too_short = serde_json::json!({ "buffer": "abab", "flags": 3 });
+        assert!(serde_json::from_value::<Packet>(too_short).is_err());
+
+        // base64 of 2 and 4 bytes is exactly as long as their hex
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        struct Tiny {
+            #[serde(with = "serde_bytes_array::base64")]
+            two: [u8; 2],
+            #[serde(with = "serde_bytes_array::base64")]
+            four: [u8; 4],
+        }
+
+        for tiny in [Tiny { two: [1, 2], four: [1, 2, 3, 4] }, Tiny { two: [0xab; 2], four: [0xab; 4] }] {
+            let json = serde_json::to_string(&tiny).unwrap();
+            assert_eq!(json.len(), r#"{"two":"","four":""}"#.len() + 4 + 8);
+            assert_eq!(serde_json::from_str::<Tiny>(&json).unwrap(), tiny);
+        }
+        assert_eq!(serde_json::from_str::<Tiny>(r#"{"two":"0102","four":"01020304"}"#).unwrap(), Tiny { two: [1, 2], four: [1, 2, 3, 4] });
+    }
+
+    #[cfg(feature = "format-json")]
+    #[bench]
+    fn bench_serialize_json_hex_normal(bencher: &mut test::Bencher) {
+        let mut output_json = vec![];
+        let input_packets = payload::packets(512, |buffer| Packet { buffer, ..Packet::default() });
+
+        bencher.iter(|| {
+            serde_json::to_writer(&mut output_json, &input_packets).unwrap();
+            test::black_box(&output_json);
+            output_json.clear();
+        })
+    }
+
+    #[cfg(feature = "format-json")]
+    #[bench]
+    fn bench_serialize_json_base64_normal(bencher: &mut test::Bencher) {
+        let mut output_json = vec![];
+        let input_packets = payload::packets(512, |buffer| Base64Packet { buffer, ..Base64Packet::default() });
+
+        bencher.iter(|| {
+            serde_json::to_writer(&mut output_json, &input_packets).unwrap();
+            test::black_box(&output_json);
+            output_json.clear();
+        })
+    }
+
+    #[cfg(all(feature = "format-json", feature = "strategy-serde-with"))]
+    #[bench]
+    fn bench_serialize_json_number_array_normal(bencher: &mut test::Bencher) {
+        let mut output_json = vec![];
+        let input_packets = payload::packets(512, |buffer| NumberArrayPacket { buffer, ..NumberArrayPacket::default() });
+
+        bencher.iter(|| {
+            serde_json::to_writer(&mut output_json, &input_packets).unwrap();
+            test::black_box(&output_json);
+            output_json.clear();
+        })
+    }
+
+    #[cfg(feature = "format-json")]
+    #[bench]
+    fn bench_deserialize_json_hex_normal(bencher: &mut test::Bencher) {
+        let input_packets = payload::packets(512, |buffer| Packet { buffer, ..Packet::default() });
+        let s = serde_json::to_vec(&input_packets).unwrap();
+
+        bencher.iter(|| {
+            test::black_box(serde_json::from_slice::<Vec<Packet>>(&s).unwrap());
+        })
+    }
+
+    #[cfg(feature = "format-json")]
+    #[bench]
+    fn bench_deserialize_json_base64_normal(bencher: &mut test::Bencher) {
+        let input_packets = payload::packets(512, |buffer| Base64Packet { buffer, ..Base64Packet::default() });
+        let s = serde_json::to_vec(&input_packets).unwrap();
+
+        bencher.iter(|| {
+            test::black_box(serde_json::from_slice::<Vec<Base64Packet>>(&s).unwrap());
+        })
+    }
+
+    #[cfg(all(feature = "format-json", feature = "strategy-serde-with"))]
+    #[bench]
+    fn bench_deserialize_json_number_array_normal(bencher: &mut test::Bencher) {
+        let input_packets = payload::packets(512, |buffer| NumberArrayPacket { buffer, ..NumberArrayPacket::default() });
+        let s = serde_json::to_vec(&input_packets).unwrap();
+
+        bencher.iter(|| {
+            test::black_box(serde_json::from_slice::<Vec<NumberArrayPacket>>(&s).unwrap());
+        })
+    }
+
+    #[cfg(feature = "format-bincode")]
+    #[bench]
+    fn bench_serialize_bincode_normal(bencher: &mut test::Bencher) {
+        let mut output_binary = vec![];
+        let input_packets = payload::packets(512, |buffer| Packet { buffer, ..Packet::default() });
+
+        bencher.iter(|| {
+            bincode::serialize_into(&mut output_binary, &input_packets).unwrap();
+            test::black_box(&output_binary);
+            output_binary.clear();
+        })
+    }
+
+    #[cfg(feature = "format-bincode")]
+    #[bench]
+    fn bench_deserialize_bincode_normal(bencher: &mut test::Bencher) {
+        let input_packets = payload::packets(512, |buffer| Packet { buffer, ..Packet::default() });
+        let s = bincode::serialize(&input_packets).unwrap();
+
+        bencher.iter(|| {
+            test::black_box(bincode::deserialize::<Vec<Packet>>(&s).unwrap());
+        })
+    }
+}
+
+#[cfg(feature = "strategy-serde-bytes")]
+#[cfg(test)]
+mod fixed_bytes_newtype {
+    use super::*;
+
+    use super::fixed_bytes::FixedBytes;
+
+    // no manual Default impl needed: FixedBytes<N> has one for any N
+    #[derive(Default, Clone, Serialize, Deserialize, Debug, PartialEq)]
+    pub struct Packet {
+        buffer: FixedBytes<PACKET_DATA_SIZE>,
+        flags: u64,
+    }
+
+    #[test]
+    fn test_fixed_bytes_conversions() {
+        let array: [u8; 4] = [0xde, 0xad, 0xbe, 0xef];
+        let bytes = FixedBytes::from(array);
+        assert_eq!(bytes.to_string(), "deadbeef");
+        assert_eq!("deadbeef".parse::<FixedBytes<4>>().unwrap(), bytes);
+        assert!("deadbe".parse::<FixedBytes<4>>().is_err());
+        assert_eq!(bytes.as_ref(), &array[..]);
+        assert_eq!(bytes.len(), 4);
+        assert_eq!(<[u8; 4]>::from(bytes), array);
+        assert_eq!(FixedBytes::<PACKET_DATA_SIZE>::default(), FixedBytes([0; PACKET_DATA_SIZE]));
+    }
+
+    #[cfg(feature = "format-bincode")]
+    #[bench]
+    fn bench_serialize_fixed_bytes_normal(bencher: &mut test::Bencher) {
+        let mut output_binary = vec![];
+        let input_packets = payload::packets(512, |buffer| Packet {
+            buffer: buffer.into(),
+            ..Packet::default()
+        });
+
+        bencher.iter(|| {
+            bincode::serialize_into(&mut output_binary, &input_packets).unwrap();
+            test::black_box(&output_binary);
+            output_binary.clear();
+        })
+    }
+
+    #[cfg(feature = "format-bincode")]
+    #[bench]
+    fn bench_deserialize_fixed_bytes_normal(bencher: &mut test::Bencher) {
+        let input_packets = payload::packets(512, |buffer| Packet {
+            buffer: buffer.into(),
+            ..Packet::default()
+        });
+        let s = bincode::serialize(&input_packets).unwrap();
+
+        bencher.iter(|| {
+            test::black_box(bincode::deserialize::<Vec<Packet>>(&s).unwrap());
+        })
+    }
+
+    #[cfg(feature = "format-bincode")]
+    #[bench]
+    fn bench_deserialize_from_fixed_bytes_normal(bencher: &mut test::Bencher) {
+        let input_packets = payload::packets(512, |buffer| Packet {
+            buffer: buffer.into(),
+            ..Packet::default()
+        });
+        let s = bincode::serialize(&input_packets).unwrap();
+
+        bencher.iter(|| {
+            let mut reader = &s[..];
+            test::black_box(bincode::deserialize_from::<_, Vec<Packet>>(&mut reader).unwrap());
+        })
+    }
+
+    #[cfg(feature = "format-json")]
+    #[bench]
+    fn bench_serialize_json_fixed_bytes_normal(bencher: &mut test::Bencher) {
+        let mut output_json = vec![];
+        let input_packets = payload::packets(512, |buffer| Packet {
+            buffer: buffer.into(),
+            ..Packet::default()
+        });
+
+        bencher.iter(|| {
+            serde_json::to_writer(&mut output_json, &input_packets).unwrap();
+            test::black_box(&output_json);
+            output_json.clear();
+        })
+    }
+
+    #[cfg(feature = "format-json")]
+    #[bench]
+    fn bench_deserialize_json_fixed_bytes_normal(bencher: &mut test::Bencher) {
+        let input_packets = payload::packets(512, |buffer| Packet {
+            buffer: buffer.into(),
+            ..Packet::default()
+        });
+        let s = serde_json::to_vec(&input_packets).unwrap();
+
+        bencher.iter(|| {
+            test::black_box(serde_json::from_slice::<Vec<Packet>>(&s).unwrap());
+        })
+    }
+}
+
+#[cfg(feature = "strategy-serde-bytes")]
+#[cfg(test)]
+mod fast_array_serde_attribute {
+    use super::*;
+
+    use serde_array_derive::fast_array_serde;
+
+    // same as serde_bytes_slice::Packet etc., but nobody has to remember the attribute
+    #[fast_array_serde]
+    #[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+    pub struct Packet {
+        buffer: [u8; PACKET_DATA_SIZE],
+        flags: u64,
+    }
+    impl Default for Packet {
+        #[inline(always)]
+        fn default() -> Self {
+            Self {
+                buffer: [0; PACKET_DATA_SIZE],
+                flags: 3,
+            }
+        }
+    }
+
+    #[fast_array_serde]
+    #[cfg_attr(not(feature = "format-json"), allow(dead_code))]
+    #[derive(Clone, Default, Serialize, Deserialize, Debug, PartialEq)]
+    pub struct OptOutPacket {
+        buffer: [u8; 32],
+        #[fast_array(skip)]
+        tag: [u8; 4],
+        flags: u64,
+    }
+
+    #[fast_array_serde(with = "crate::serde_bytes_array::base64")]
+    #[cfg_attr(not(feature = "format-json"), allow(dead_code))]
+    #[derive(Clone, Default, Serialize, Deserialize, Debug, PartialEq)]
+    pub struct Signature {
+        base64: [u8; 4],
+        #[serde(with = "crate::serde_bytes_array")]
+        hex: [u8; 4],
+    }
+
+    #[cfg(all(feature = "format-bincode", feature = "format-json"))]
+    #[test]
+    fn test_fast_array_serde_rewrites_byte_arrays() {
+        // length prefix + buffer + flags, the same bytes as #[serde(with = "serde_bytes_array")]
+        let packet = Packet::default();
+        let binary = bincode::serialize(&packet).unwrap();
+        assert_eq!(binary.len(), 8 + PACKET_DATA_SIZE + 8);
+        assert_eq!(bincode::deserialize::<Packet>(&binary).unwrap(), packet);
+
+        // the opted-out tag stays a 4-byte tuple without length prefix
+        let packet = OptOutPacket {
+            tag: *b"abcd",
+            ..OptOutPacket::default()
+        };
+        let binary = bincode::serialize(&packet).unwrap();
+        assert_eq!(binary.len(), 8 + 32 + 4 + 8);
+        assert_eq!(bincode::deserialize::<OptOutPacket>(&binary).unwrap(), packet);
+        assert!(serde_json::to_string(&packet).unwrap().contains("\"tag\":[97,98,99,100]"));
+
+        // custom adapter path, and fields with their own serde attribute are left alone
+        let signature = Signature {
+            base64: [0xde, 0xad, 0xbe, 0xef],
+            hex: [0xde, 0xad, 0xbe, 0xef],
+        };
+        let json = serde_json::to_string(&signature).unwrap();
+        assert_eq!(json, r#"{"base64":"3q2+7w==","hex":"deadbeef"}"#);
+        assert_eq!(serde_json::from_str::<Signature>(&json).unwrap(), signature);
+    }
+
+    #[cfg(feature = "format-bincode")]
+    #[bench]
+    fn bench_serialize_fast_array_serde_normal(bencher: &mut test::Bencher) {
+        let mut output_binary = vec![];
+        let input_packets = payload::packets(512, |buffer| Packet { buffer, ..Packet::default() });
+
+        bencher.iter(|| {
+            bincode::serialize_into(&mut output_binary, &input_packets).unwrap();
+            test::black_box(&output_binary);
+            output_binary.clear();
+        })
+    }
+
+    #[cfg(feature = "format-bincode")]
+    #[bench]
+    fn bench_deserialize_fast_array_serde_normal(bencher: &mut test::Bencher) {
+        let input_packets = payload::packets(512, |buffer| Packet { buffer, ..Packet::default() });
+        let s = bincode::serialize(&input_packets).unwrap();
+
+        bencher.iter(|| {
+            test::black_box(bincode::deserialize::<Vec<Packet>>(&s).unwrap());
+        })
+    }
+}
+
+#[cfg(all(feature = "strategy-serde-with", feature = "strategy-serde-bytes"))]
+#[cfg(test)]
+mod serde_as_fast_bytes {
+    use super::*;
+
+    use {
+        super::fast_bytes::FastBytes,
+        serde_with::serde_as,
+        std::collections::HashMap,
+    };
+
+    #[serde_as]
+    #[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+    pub struct Packet {
+        #[serde_as(as = "FastBytes")]
+        buffer: [u8; PACKET_DATA_SIZE],
+        flags: u64,
+    }
+    impl Default for Packet {
+        #[inline(always)]
+        fn default() -> Self {
+            Self {
+                buffer: [0; PACKET_DATA_SIZE],
+                flags: 3,
+            }
+        }
+    }
+
+    // the nested forms, each with the slow `[_; N]` tuple encoding next to it
+    #[serde_as]
+    #[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+    pub struct OptionPacket {
+        #[serde_as(as = "Option<FastBytes>")]
+        buffer: Option<[u8; PACKET_DATA_SIZE]>,
+        flags: u64,
+    }
+    impl Default for OptionPacket {
+        #[inline(always)]
+        fn default() -> Self {
+            Self {
+                buffer: Some([0; PACKET_DATA_SIZE]),
+                flags: 3,
+            }
+        }
+    }
+
+    #[serde_as]
+    #[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+    pub struct SlowOptionPacket {
+        #[serde_as(as = "Option<[_; PACKET_DATA_SIZE]>")]
+        buffer: Option<[u8; PACKET_DATA_SIZE]>,
+        flags: u64,
+    }
+    impl Default for SlowOptionPacket {
+        #[inline(always)]
+        fn default() -> Self {
+            Self {
+                buffer: Some([0; PACKET_DATA_SIZE]),
+                flags: 3,
+            }
+        }
+    }
+
+    #[serde_as]
+    #[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+    pub struct KeysPacket {
+        #[serde_as(as = "Vec<FastBytes>")]
+        keys: Vec<[u8; 32]>,
+        flags: u64,
+    }
+    impl Default for KeysPacket {
+        #[inline(always)]
+        fn default() -> Self {
+            Self {
+                keys: vec![[0; 32]; 38],
+                flags: 3,
+            }
+        }
+    }
+
+    // [u8; 32] is still within what serde implements Serialize for, so no attribute needed
+    #[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+    pub struct SlowKeysPacket {
+        keys: Vec<[u8; 32]>,
+        flags: u64,
+    }
+    impl Default for SlowKeysPacket {
+        #[inline(always)]
+        fn default() -> Self {
+            Self {
+                keys: vec![[0; 32]; 38],
+                flags: 3,
+            }
+        }
+    }
+
+    #[serde_as]
+    #[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+    pub struct SignaturesPacket {
+        #[serde_as(as = "HashMap<_, FastBytes>")]
+        signatures: HashMap<u64, [u8; 64]>,
+        flags: u64,
+    }
+    impl Default for SignaturesPacket {
+        #[inline(always)]
+        fn default() -> Self {
+            Self {
+                signatures: (0..19).map(|i| (i, [0; 64])).collect(),
+                flags: 3,
+            }
+        }
+    }
+
+    #[serde_as]
+    #[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+    pub struct SlowSignaturesPacket {
+        #[serde_as(as = "HashMap<_, [_; 64]>")]
+        signatures: HashMap<u64, [u8; 64]>,
+        flags: u64,
+    }
+    impl Default for SlowSignaturesPacket {
+        #[inline(always)]
+        fn default() -> Self {
+            Self {
+                signatures: (0..19).map(|i| (i, [0; 64])).collect(),
+                flags: 3,
+            }
+        }
+    }
+
+    #[cfg(all(feature = "format-bincode", feature = "format-json"))]
+    #[test]
+    fn test_nested_fast_bytes_round_trip() {
+        fn round_trip<T: Default + PartialEq + std::fmt::Debug + serde::Serialize + serde::de::DeserializeOwned>() {
+            let value = T::default();
+            assert_eq!(bincode::deserialize::<T>(&bincode::serialize(&value).unwrap()).unwrap(), value);
+            assert_eq!(serde_json::from_str::<T>(&serde_json::to_string(&value).unwrap()).unwrap(), value);
+        }
+        round_trip::<Packet>();
+        round_trip::<OptionPacket>();
+        round_trip::<KeysPacket>();
+        round_trip::<SignaturesPacket>();
+
+        // FastBytes writes 8-byte length prefixes; the tuple encoding doesn't, but walks every byte
+        let keys = bincode::serialize(&KeysPacket::default()).unwrap();
+        let slow_keys = bincode::serialize(&SlowKeysPacket::default()).unwrap();
+        assert_eq!(keys.len(), slow_keys.len() + 38 * 8);
+    }
+
+    // 38 keys or 19 signatures fit in a packet buffer, so the nested forms take their bytes from the
+    // same payload as the others
+    #[cfg(feature = "format-bincode")]
+    fn chunks<const N: usize>(buffer: &[u8; PACKET_DATA_SIZE]) -> impl Iterator<Item = [u8; N]> + '_ {
+        buffer.chunks_exact(N).map(|chunk| chunk.try_into().unwrap())
+    }
+
+    #[cfg(feature = "format-bincode")]
+    fn packets() -> Vec<Packet> {
+        payload::packets(512, |buffer| Packet { buffer, ..Packet::default() })
+    }
+
+    #[cfg(feature = "format-bincode")]
+    fn option_packets() -> Vec<OptionPacket> {
+        payload::packets(512, |buffer| OptionPacket {
+            buffer: Some(buffer),
+            ..OptionPacket::default()
+        })
+    }
+
+    #[cfg(feature = "format-bincode")]
+    fn slow_option_packets() -> Vec<SlowOptionPacket> {
+        payload::packets(512, |buffer| SlowOptionPacket {
+            buffer: Some(buffer),
+            ..SlowOptionPacket::default()
+        })
+    }
+
+    #[cfg(feature = "format-bincode")]
+    fn keys_packets() -> Vec<KeysPacket> {
+        payload::packets(512, |buffer| KeysPacket {
+            keys: chunks(&buffer).collect(),
+            ..KeysPacket::default()
+        })
+    }
+
+    #[cfg(feature = "format-bincode")]
+    fn slow_keys_packets() -> Vec<SlowKeysPacket> {
+        payload::packets(512, |buffer| SlowKeysPacket {
+            keys: chunks(&buffer).collect(),
+            ..SlowKeysPacket::default()
+        })
+    }
+
+    #[cfg(feature = "format-bincode")]
+    fn signatures_packets() -> Vec<SignaturesPacket> {
+        payload::packets(512, |buffer| SignaturesPacket {
+            signatures: (0..).zip(chunks(&buffer)).collect(),
+            ..SignaturesPacket::default()
+        })
+    }
+
+    #[cfg(feature = "format-bincode")]
+    fn slow_signatures_packets() -> Vec<SlowSignaturesPacket> {
+        payload::packets(512, |buffer| SlowSignaturesPacket {
+            signatures: (0..).zip(chunks(&buffer)).collect(),
+            ..SlowSignaturesPacket::default()
+        })
+    }
+
+    #[cfg(feature = "format-bincode")]
+    #[bench]
+    fn bench_serialize_fast_bytes_normal(bencher: &mut test::Bencher) {
+        bench_util::bench_serialize(bencher, &packets())
+    }
+
+    #[cfg(feature = "format-bincode")]
+    #[bench]
+    fn bench_deserialize_fast_bytes_normal(bencher: &mut test::Bencher) {
+        bench_util::bench_deserialize(bencher, &packets())
+    }
+
+    #[cfg(feature = "format-bincode")]
+    #[bench]
+    fn bench_serialize_option_fast_bytes(bencher: &mut test::Bencher) {
+        bench_util::bench_serialize(bencher, &option_packets())
+    }
+
+    #[cfg(feature = "format-bincode")]
+    #[bench]
+    fn bench_serialize_option_tuple(bencher: &mut test::Bencher) {
+        bench_util::bench_serialize(bencher, &slow_option_packets())
+    }
+
+    #[cfg(feature = "format-bincode")]
+    #[bench]
+    fn bench_deserialize_option_fast_bytes(bencher: &mut test::Bencher) {
+        bench_util::bench_deserialize(bencher, &option_packets())
+    }
+
+    #[cfg(feature = "format-bincode")]
+    #[bench]
+    fn bench_deserialize_option_tuple(bencher: &mut test::Bencher) {
+        bench_util::bench_deserialize(bencher, &slow_option_packets())
+    }
+
+    #[cfg(feature = "format-bincode")]
+    #[bench]
+    fn bench_serialize_vec_fast_bytes(bencher: &mut test::Bencher) {
+        bench_util::bench_serialize(bencher, &keys_packets())
+    }
+
+    #[cfg(feature = "format-bincode")]
+    #[bench]
+    fn bench_serialize_vec_tuple(bencher: &mut test::Bencher) {
+        bench_util::bench_serialize(bencher, &slow_keys_packets())
+    }
+
+    #[cfg(feature = "format-bincode")]
+    #[bench]
+    fn bench_deserialize_vec_fast_bytes(bencher: &mut test::Bencher) {
+        bench_util::bench_deserialize(bencher, &keys_packets())
+    }
+
+    #[cfg(feature = "format-bincode")]
+    #[bench]
+    fn bench_deserialize_vec_tuple(bencher: &mut test::Bencher) {
+        bench_util::bench_deserialize(bencher, &slow_keys_packets())
+    }
+
+    #[cfg(feature = "format-bincode")]
+    #[bench]
+    fn bench_serialize_hash_map_fast_bytes(bencher: &mut test::Bencher) {
+        bench_util::bench_serialize(bencher, &signatures_packets())
+    }
+
+    #[cfg(feature = "format-bincode")]
+    #[bench]
+    fn bench_serialize_hash_map_tuple(bencher: &mut test::Bencher) {
+        bench_util::bench_serialize(bencher, &slow_signatures_packets())
+    }
+
+    #[cfg(feature = "format-bincode")]
+    #[bench]
+    fn bench_deserialize_hash_map_fast_bytes(bencher: &mut test::Bencher) {
+        bench_util::bench_deserialize(bencher, &signatures_packets())
+    }
+
+    #[cfg(feature = "format-bincode")]
+    #[bench]
+    fn bench_deserialize_hash_map_tuple(bencher: &mut test::Bencher) {
+        bench_util::bench_deserialize(bencher, &slow_signatures_packets())
+    }
+}
+
+// the batch benches the strategy modules below share: 512 packets through bincode or JSON, plus
+// the serde_with comparison packets, generic over what they carry so each module only has to
+// declare its own strategy
+#[cfg(test)]
+mod bench_util {
+    use super::*;
+
+    /// 512 copies of `packet`
+    pub fn batch<T: Clone>(packet: T) -> Vec<T> {
+        vec![packet; 512]
+    }
+
+    #[cfg(feature = "format-bincode")]
+    pub fn bench_serialize<T: serde::Serialize>(bencher: &mut test::Bencher, input_packets: &[T]) {
+        let mut output_binary = vec![];
+
+        bencher.iter(|| {
+            bincode::serialize_into(&mut output_binary, input_packets).unwrap();
+            test::black_box(&output_binary);
+            output_binary.clear();
+        })
+    }
+
+    #[cfg(feature = "format-bincode")]
+    pub fn bench_deserialize<T: serde::Serialize + serde::de::DeserializeOwned>(bencher: &mut test::Bencher, input_packets: &[T]) {
+        let s = bincode::serialize(input_packets).unwrap();
+
+        bencher.iter(|| {
+            test::black_box(bincode::deserialize::<Vec<T>>(&s).unwrap());
+        })
+    }
+
+    #[cfg(feature = "format-json")]
+    pub fn bench_serialize_json<T: serde::Serialize>(bencher: &mut test::Bencher, input_packets: &[T]) {
+        let mut output_json = vec![];
+
+        bencher.iter(|| {
+            serde_json::to_writer(&mut output_json, input_packets).unwrap();
+            test::black_box(&output_json);
+            output_json.clear();
+        })
+    }
+
+    #[cfg(feature = "format-json")]
+    pub fn bench_deserialize_json<T: serde::Serialize + serde::de::DeserializeOwned>(bencher: &mut test::Bencher, input_packets: &[T]) {
+        let s = serde_json::to_vec(input_packets).unwrap();
+
+        bencher.iter(|| {
+            test::black_box(serde_json::from_slice::<Vec<T>>(&s).unwrap());
+        })
+    }
+
+    // `T` with serde's own impl
+    #[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+    pub struct SerdePacket<T> {
+        data: T,
+        flags: u64,
+    }
+    impl<T> SerdePacket<T> {
+        pub fn new(data: T) -> Self {
+            Self { data, flags: 3 }
+        }
+    }
+
+    // `[T; N]` as serde_with's `[_; N]`: one tuple element per item. `M` is what follows the array,
+    // plain flags unless a module brings its own header
+    #[cfg(feature = "strategy-serde-with")]
+    #[serde_with::serde_as]
+    #[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+    #[serde(bound(
+        serialize = "T: serde::Serialize, M: serde::Serialize",
+        deserialize = "T: serde::Deserialize<'de>, M: serde::Deserialize<'de>"
+    ))]
+    pub struct SerdeAsPacket<T, const N: usize, M = u64> {
+        #[serde_as(as = "[_; N]")]
+        pub(crate) data: [T; N],
+        pub(crate) meta: M,
+    }
+    #[cfg(feature = "strategy-serde-with")]
+    impl<T, const N: usize> SerdeAsPacket<T, N> {
+        pub fn new(data: [T; N]) -> Self {
+            Self { data, meta: 3 }
+        }
+    }
+    #[cfg(feature = "strategy-serde-with")]
+    impl<T, const N: usize, M> SerdeAsPacket<T, N, M> {
+        #[cfg_attr(not(feature = "packet-meta"), allow(dead_code))]
+        pub fn with_meta(data: [T; N], meta: M) -> Self {
+            Self { data, meta }
+        }
+    }
+
+    // `[T; N]` through serde_arrays, tuple encoded like the above
+    #[cfg(feature = "strategy-serde-arrays")]
+    #[cfg_attr(not(feature = "packet-meta"), allow(dead_code))]
+    #[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+    #[serde(bound(
+        serialize = "T: serde::Serialize, M: serde::Serialize",
+        deserialize = "T: serde::Deserialize<'de>, M: serde::Deserialize<'de>"
+    ))]
+    pub struct SerdeArraysPacket<T, const N: usize, M = u64> {
+        #[serde(with = "serde_arrays")]
+        data: [T; N],
+        meta: M,
+    }
+    #[cfg(feature = "strategy-serde-arrays")]
+    #[cfg_attr(not(feature = "packet-meta"), allow(dead_code))]
+    impl<T, const N: usize, M> SerdeArraysPacket<T, N, M> {
+        pub fn with_meta(data: [T; N], meta: M) -> Self {
+            Self { data, meta }
+        }
+    }
+
+    // `[u8; N]` as serde_with's `Bytes`: one length-prefixed byte string
+    #[cfg(feature = "strategy-serde-with")]
+    #[cfg_attr(not(feature = "packet-meta"), allow(dead_code))]
+    #[serde_with::serde_as]
+    #[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+    pub struct SerdeAsBytesPacket<const N: usize, M = u64> {
+        #[serde_as(as = "serde_with::Bytes")]
+        data: [u8; N],
+        meta: M,
+    }
+    #[cfg(feature = "strategy-serde-with")]
+    #[cfg_attr(not(feature = "packet-meta"), allow(dead_code))]
+    impl<const N: usize, M> SerdeAsBytesPacket<N, M> {
+        pub fn with_meta(data: [u8; N], meta: M) -> Self {
+            Self { data, meta }
+        }
+    }
+
+    // `[[T; N]; M]` as nested tuples
+    #[cfg(feature = "strategy-serde-with")]
+    #[serde_with::serde_as]
+    #[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+    #[serde(bound(serialize = "T: serde::Serialize", deserialize = "T: serde::Deserialize<'de>"))]
+    pub struct SerdeAs2dPacket<T, const N: usize, const M: usize> {
+        #[serde_as(as = "[[_; N]; M]")]
+        data: [[T; N]; M],
+        flags: u64,
+    }
+    #[cfg(feature = "strategy-serde-with")]
+    impl<T, const N: usize, const M: usize> SerdeAs2dPacket<T, N, M> {
+        pub fn new(data: [[T; N]; M]) -> Self {
+            Self { data, flags: 3 }
+        }
+    }
+
+    // `[[u8; N]; M]` as serde_with's `[Bytes; M]`: one length-prefixed byte string per row
+    #[cfg(feature = "strategy-serde-with")]
+    #[serde_with::serde_as]
+    #[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+    pub struct SerdeAsBytesRowsPacket<const N: usize, const M: usize> {
+        #[serde_as(as = "[serde_with::Bytes; M]")]
+        data: [[u8; N]; M],
+        flags: u64,
+    }
+    #[cfg(feature = "strategy-serde-with")]
+    impl<const N: usize, const M: usize> SerdeAsBytesRowsPacket<N, M> {
+        pub fn new(data: [[u8; N]; M]) -> Self {
+            Self { data, flags: 3 }
+        }
+    }
+
+    // `Vec<[T; N]>` as serde_with's `Vec<[_; N]>`: one tuple walk per element
+    #[cfg(feature = "strategy-serde-with")]
+    #[serde_with::serde_as]
+    #[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+    #[serde(bound(serialize = "T: serde::Serialize", deserialize = "T: serde::Deserialize<'de>"))]
+    pub struct SerdeAsVecPacket<T, const N: usize> {
+        #[serde_as(as = "Vec<[_; N]>")]
+        data: Vec<[T; N]>,
+        flags: u64,
+    }
+    #[cfg(feature = "strategy-serde-with")]
+    impl<T, const N: usize> SerdeAsVecPacket<T, N> {
+        pub fn new(data: Vec<[T; N]>) -> Self {
+            Self { data, flags: 3 }
+        }
+    }
+
+    // `Vec<[u8; N]>` with one serde_bytes_array length prefix per element
+    #[cfg(all(feature = "strategy-serde-with", feature = "strategy-serde-bytes"))]
+    #[serde_with::serde_as]
+    #[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+    pub struct FastBytesVecPacket<const N: usize> {
+        #[serde_as(as = "Vec<fast_bytes::FastBytes>")]
+        data: Vec<[u8; N]>,
+        flags: u64,
+    }
+    #[cfg(all(feature = "strategy-serde-with", feature = "strategy-serde-bytes"))]
+    impl<const N: usize> FastBytesVecPacket<N> {
+        pub fn new(data: Vec<[u8; N]>) -> Self {
+            Self { data, flags: 3 }
+        }
+    }
+}
+
+#[cfg(test)]
+mod serde_le_array_samples {
+    use super::*;
+
+    pub const SAMPLE_COUNT: usize = 512;
+
+    #[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+    pub struct Packet {
+        #[serde(with = "serde_le_array")]
+        samples: [i16; SAMPLE_COUNT],
+        flags: u64,
+    }
+    impl Default for Packet {
+        #[inline(always)]
+        fn default() -> Self {
+            Self {
+                samples: [0; SAMPLE_COUNT],
+                flags: 3,
+            }
+        }
+    }
+
+    #[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+    pub struct FloatPacket {
+        #[serde(with = "serde_le_array")]
+        samples: [f32; SAMPLE_COUNT],
+        flags: u64,
+    }
+    impl Default for FloatPacket {
+        #[inline(always)]
+        fn default() -> Self {
+            Self {
+                samples: [0.0; SAMPLE_COUNT],
+                flags: 3,
+            }
+        }
+    }
+
+    #[cfg(all(feature = "format-bincode", feature = "format-json"))]
+    #[test]
+    fn test_le_array_round_trip() {
+        let mut packet = Packet::default();
+        packet.samples[0] = 0x0102;
+        packet.samples[1] = -2;
+        let binary = bincode::serialize(&packet).unwrap();
+        assert_eq!(binary.len(), 8 + SAMPLE_COUNT * 2 + 8);
+        assert_eq!(&binary[8..12], &[0x02, 0x01, 0xfe, 0xff]);
+        assert_eq!(bincode::deserialize::<Packet>(&binary).unwrap(), packet);
+        let mut reader = &binary[..];
+        assert_eq!(bincode::deserialize_from::<_, Packet>(&mut reader).unwrap(), packet);
+        assert_eq!(serde_json::from_str::<Packet>(&serde_json::to_string(&packet).unwrap()).unwrap(), packet);
+
+        let mut packet = FloatPacket::default();
+        packet.samples[SAMPLE_COUNT - 1] = -1.5;
+        let binary = bincode::serialize(&packet).unwrap();
+        assert_eq!(&binary[8 + (SAMPLE_COUNT - 1) * 4..][..4], &(-1.5f32).to_le_bytes());
+        assert_eq!(bincode::deserialize::<FloatPacket>(&binary).unwrap(), packet);
+
+        // one byte short of the last sample
+        let mut truncated = bincode::serialize(&Packet::default()).unwrap();
+        truncated[..8].copy_from_slice(&(SAMPLE_COUNT as u64 * 2 - 1).to_le_bytes());
+        truncated.remove(8);
+        assert!(bincode::deserialize::<Packet>(&truncated).is_err());
+
+        // byte-by-byte input one element too long or too short
+        #[derive(Deserialize, Debug)]
+        struct Pair(#[serde(with = "serde_le_array")] [u16; 2]);
+        assert_eq!(serde_json::from_str::<Pair>("[2,1,254,255]").unwrap().0, [0x0102, 0xfffe]);
+        let error = serde_json::from_str::<Pair>("[2,1,254,255,0]").unwrap_err();
+        assert!(error.to_string().contains("invalid length 5"), "{}", error);
+        assert!(serde_json::from_str::<Pair>("[2,1,254]").is_err());
+    }
+
+    #[cfg(feature = "format-bincode")]
+    #[bench]
+    fn bench_serialize_le_array_i16(bencher: &mut test::Bencher) {
+        bench_util::bench_serialize(bencher, &bench_util::batch(Packet::default()))
+    }
+
+    #[cfg(all(feature = "format-bincode", feature = "strategy-serde-with"))]
+    #[bench]
+    fn bench_serialize_serde_as_i16(bencher: &mut test::Bencher) {
+        bench_util::bench_serialize(bencher, &bench_util::batch(bench_util::SerdeAsPacket::new([0i16; SAMPLE_COUNT])))
+    }
+
+    #[cfg(feature = "format-bincode")]
+    #[bench]
+    fn bench_serialize_le_array_f32(bencher: &mut test::Bencher) {
+        bench_util::bench_serialize(bencher, &bench_util::batch(FloatPacket::default()))
+    }
+
+    #[cfg(all(feature = "format-bincode", feature = "strategy-serde-with"))]
+    #[bench]
+    fn bench_serialize_serde_as_f32(bencher: &mut test::Bencher) {
+        bench_util::bench_serialize(bencher, &bench_util::batch(bench_util::SerdeAsPacket::new([0f32; SAMPLE_COUNT])))
+    }
+
+    #[cfg(feature = "format-bincode")]
+    #[bench]
+    fn bench_deserialize_le_array_i16(bencher: &mut test::Bencher) {
+        bench_util::bench_deserialize(bencher, &bench_util::batch(Packet::default()))
+    }
+
+    #[cfg(all(feature = "format-bincode", feature = "strategy-serde-with"))]
+    #[bench]
+    fn bench_deserialize_serde_as_i16(bencher: &mut test::Bencher) {
+        bench_util::bench_deserialize(bencher, &bench_util::batch(bench_util::SerdeAsPacket::new([0i16; SAMPLE_COUNT])))
+    }
+
+    #[cfg(feature = "format-bincode")]
+    #[bench]
+    fn bench_deserialize_le_array_f32(bencher: &mut test::Bencher) {
+        bench_util::bench_deserialize(bencher, &bench_util::batch(FloatPacket::default()))
+    }
+
+    #[cfg(all(feature = "format-bincode", feature = "strategy-serde-with"))]
+    #[bench]
+    fn bench_deserialize_serde_as_f32(bencher: &mut test::Bencher) {
+        bench_util::bench_deserialize(bencher, &bench_util::batch(bench_util::SerdeAsPacket::new([0f32; SAMPLE_COUNT])))
+    }
+}
+
+#[cfg(test)]
+mod serde_boxed_bytes_array_huge {
+    use super::*;
+
+    pub const BLOB_SIZE: usize = 1 << 20;
+    // small enough for the "build on the stack, then box" comparison to survive a 2 MiB test thread
+    pub const STACK_BLOB_SIZE: usize = 256 << 10;
+
+    #[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+    pub struct Blob<const N: usize> {
+        #[serde(with = "serde_boxed_bytes_array")]
+        buffer: Box<[u8; N]>,
+        flags: u64,
+    }
+    impl<const N: usize> Default for Blob<N> {
+        #[inline(always)]
+        fn default() -> Self {
+            Self {
+                buffer: vec![0; N].into_boxed_slice().try_into().unwrap(),
+                flags: 3,
+            }
+        }
+    }
+
+    // what you'd write without the adapter: `[u8; N]` is built on the stack, then moved into the box
+    #[cfg(feature = "strategy-serde-bytes")]
+    #[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+    pub struct StackBlob<const N: usize> {
+        #[serde(serialize_with = "serialize_boxed", deserialize_with = "deserialize_boxed")]
+        buffer: Box<[u8; N]>,
+        flags: u64,
+    }
+    #[cfg(feature = "strategy-serde-bytes")]
+    impl<const N: usize> Default for StackBlob<N> {
+        #[inline(always)]
+        fn default() -> Self {
+            Self {
+                buffer: vec![0; N].into_boxed_slice().try_into().unwrap(),
+                flags: 3,
+            }
+        }
+    }
+
+    #[cfg(feature = "strategy-serde-bytes")]
+    #[allow(clippy::borrowed_box)]
+    fn serialize_boxed<S: serde::Serializer, const N: usize>(bytes: &Box<[u8; N]>, serializer: S) -> Result<S::Ok, S::Error> {
+        serde_bytes_array::serialize(&bytes[..], serializer)
+    }
+
+    #[cfg(feature = "strategy-serde-bytes")]
+    fn deserialize_boxed<'de, D: serde::Deserializer<'de>, const N: usize>(deserializer: D) -> Result<Box<[u8; N]>, D::Error> {
+        serde_bytes_array::deserialize(deserializer).map(Box::new)
+    }
+
+    #[cfg(feature = "format-bincode")]
+    #[test]
+    fn test_boxed_bytes_array_1_mib_on_default_thread_stack() {
+        // deliberately not the test harness's own thread: std::thread::spawn gets the default stack
+        std::thread::spawn(|| {
+            let mut blob = Blob::<BLOB_SIZE>::default();
+            blob.buffer[BLOB_SIZE - 1] = 7;
+            let binary = bincode::serialize(&blob).unwrap();
+            assert_eq!(binary.len(), 8 + BLOB_SIZE + 8);
+            assert_eq!(bincode::deserialize::<Blob<BLOB_SIZE>>(&binary).unwrap(), blob);
+            let mut reader = &binary[..];
+            assert_eq!(bincode::deserialize_from::<_, Blob<BLOB_SIZE>>(&mut reader).unwrap(), blob);
+
+            assert!(bincode::deserialize::<Blob<{ BLOB_SIZE - 1 }>>(&binary).is_err());
+        })
+        .join()
+        .unwrap();
+    }
+
+    #[cfg(feature = "format-json")]
+    #[test]
+    fn test_boxed_bytes_array_from_sequence() {
+        let blob = Blob::<4> {
+            buffer: Box::new([1, 2, 3, 4]),
+            flags: 3,
+        };
+        let json = serde_json::to_string(&blob).unwrap();
+        assert_eq!(json, r#"{"buffer":[1,2,3,4],"flags":3}"#);
+        assert_eq!(serde_json::from_str::<Blob<4>>(&json).unwrap(), blob);
+        assert!(serde_json::from_str::<Blob<4>>(r#"{"buffer":[1,2,3],"flags":3}"#).is_err());
+        assert!(serde_json::from_str::<Blob<4>>(r#"{"buffer":[1,2,3,4,5],"flags":3}"#).is_err());
+    }
+
+    #[cfg(feature = "format-bincode")]
+    #[bench]
+    fn bench_deserialize_boxed_1_mib(bencher: &mut test::Bencher) {
+        bench_util::bench_deserialize(bencher, &[Blob::<BLOB_SIZE>::default()])
+    }
+
+    #[cfg(feature = "format-bincode")]
+    #[bench]
+    fn bench_deserialize_boxed_256_kib(bencher: &mut test::Bencher) {
+        bench_util::bench_deserialize(bencher, &[Blob::<STACK_BLOB_SIZE>::default()])
+    }
+
+    #[cfg(all(feature = "format-bincode", feature = "strategy-serde-bytes"))]
+    #[bench]
+    fn bench_deserialize_stack_then_box_256_kib(bencher: &mut test::Bencher) {
+        bench_util::bench_deserialize(bencher, &[StackBlob::<STACK_BLOB_SIZE>::default()])
+    }
+}
+
+#[cfg(test)]
+mod serde_array_uninit_strings {
+    use super::*;
+
+    pub const NAME_COUNT: usize = 100;
+
+    fn names() -> [String; NAME_COUNT] {
+        core::array::from_fn(|i| format!("name-{}", i))
+    }
+
+    #[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+    pub struct Packet {
+        #[serde(with = "serde_array_uninit")]
+        names: [String; NAME_COUNT],
+        flags: u64,
+    }
+    impl Default for Packet {
+        fn default() -> Self {
+            Self {
+                names: names(),
+                flags: 3,
+            }
+        }
+    }
+
+    #[cfg(all(feature = "format-bincode", feature = "format-json"))]
+    #[test]
+    fn test_array_uninit_round_trip() {
+        let packet = Packet::default();
+        assert_eq!(bincode::deserialize::<Packet>(&bincode::serialize(&packet).unwrap()).unwrap(), packet);
+        assert_eq!(serde_json::from_str::<Packet>(&serde_json::to_string(&packet).unwrap()).unwrap(), packet);
+    }
+
+    #[cfg(feature = "format-json")]
+    #[test]
+    fn test_array_uninit_drops_written_elements_on_error() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        static LIVE: AtomicUsize = AtomicUsize::new(0);
+
+        // neither Copy nor Default, and counts its live instances
+        #[derive(Debug)]
+        struct Counted(#[allow(dead_code)] String);
+        impl<'de> serde::Deserialize<'de> for Counted {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let s = <String as serde::Deserialize>::deserialize(deserializer)?;
+                LIVE.fetch_add(1, Ordering::SeqCst);
+                Ok(Counted(s))
+            }
+        }
+        impl Drop for Counted {
+            fn drop(&mut self) {
+                LIVE.fetch_sub(1, Ordering::SeqCst);
+            }
+        }
+
+        #[allow(dead_code)]
+        #[derive(Deserialize)]
+        struct Holder(#[serde(with = "serde_array_uninit")] [Counted; 4]);
+
+        let ok = serde_json::from_str::<Holder>(r#"["a","b","c","d"]"#).unwrap();
+        assert_eq!(LIVE.load(Ordering::SeqCst), 4);
+        drop(ok);
+        assert_eq!(LIVE.load(Ordering::SeqCst), 0);
+
+        // fails on the third element, after two were written
+        assert!(serde_json::from_str::<Holder>(r#"["a","b",3,"d"]"#).is_err());
+        assert_eq!(LIVE.load(Ordering::SeqCst), 0);
+        // runs out of elements
+        assert!(serde_json::from_str::<Holder>(r#"["a","b","c"]"#).is_err());
+        assert_eq!(LIVE.load(Ordering::SeqCst), 0);
+        assert!(serde_json::from_str::<Holder>(r#"["a","b","c","d","e"]"#).is_err());
+        assert_eq!(LIVE.load(Ordering::SeqCst), 0);
+    }
+
+    #[cfg(feature = "format-bincode")]
+    #[bench]
+    fn bench_serialize_array_uninit_strings(bencher: &mut test::Bencher) {
+        bench_util::bench_serialize(bencher, &bench_util::batch(Packet::default()))
+    }
+
+    #[cfg(all(feature = "format-bincode", feature = "strategy-serde-with"))]
+    #[bench]
+    fn bench_serialize_serde_as_strings(bencher: &mut test::Bencher) {
+        bench_util::bench_serialize(bencher, &bench_util::batch(bench_util::SerdeAsPacket::new(names())))
+    }
+
+    #[cfg(feature = "format-bincode")]
+    #[bench]
+    fn bench_deserialize_array_uninit_strings(bencher: &mut test::Bencher) {
+        bench_util::bench_deserialize(bencher, &bench_util::batch(Packet::default()))
+    }
+
+    #[cfg(all(feature = "format-bincode", feature = "strategy-serde-with"))]
+    #[bench]
+    fn bench_deserialize_serde_as_strings(bencher: &mut test::Bencher) {
+        bench_util::bench_deserialize(bencher, &bench_util::batch(bench_util::SerdeAsPacket::new(names())))
+    }
+}
+
+#[cfg(test)]
+mod serde_bytes_array_2d_signatures {
+    use super::*;
+
+    pub const SIGNATURE_SIZE: usize = 64;
+    pub const SIGNATURE_COUNT: usize = 8;
+
+    #[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+    pub struct Packet {
+        #[serde(with = "serde_bytes_array_2d")]
+        signatures: [[u8; SIGNATURE_SIZE]; SIGNATURE_COUNT],
+        flags: u64,
+    }
+    impl Default for Packet {
+        #[inline(always)]
+        fn default() -> Self {
+            Self {
+                signatures: [[0; SIGNATURE_SIZE]; SIGNATURE_COUNT],
+                flags: 3,
+            }
+        }
+    }
+
+    #[cfg(all(feature = "format-bincode", feature = "format-json"))]
+    #[test]
+    fn test_bytes_array_2d_round_trip() {
+        let mut packet = Packet::default();
+        packet.signatures[1][0] = 1;
+        packet.signatures[SIGNATURE_COUNT - 1][SIGNATURE_SIZE - 1] = 2;
+        let binary = bincode::serialize(&packet).unwrap();
+        assert_eq!(binary.len(), 8 + SIGNATURE_SIZE * SIGNATURE_COUNT + 8);
+        assert_eq!(binary[8 + SIGNATURE_SIZE], 1);
+        assert_eq!(bincode::deserialize::<Packet>(&binary).unwrap(), packet);
+        let mut reader = &binary[..];
+        assert_eq!(bincode::deserialize_from::<_, Packet>(&mut reader).unwrap(), packet);
+        assert_eq!(serde_json::from_str::<Packet>(&serde_json::to_string(&packet).unwrap()).unwrap(), packet);
+
+        // right byte count for a different shape is still the wrong length
+        #[allow(dead_code)]
+        #[derive(Deserialize, Debug)]
+        struct Reshaped(#[serde(with = "serde_bytes_array_2d")] [[u8; SIGNATURE_SIZE]; SIGNATURE_COUNT - 1]);
+        assert!(bincode::deserialize::<Reshaped>(&binary).is_err());
+    }
+
+    #[cfg(feature = "format-bincode")]
+    #[bench]
+    fn bench_serialize_bytes_array_2d(bencher: &mut test::Bencher) {
+        bench_util::bench_serialize(bencher, &bench_util::batch(Packet::default()))
+    }
+
+    #[cfg(all(feature = "format-bincode", feature = "strategy-serde-with"))]
+    #[bench]
+    fn bench_serialize_serde_as_2d(bencher: &mut test::Bencher) {
+        bench_util::bench_serialize(bencher, &bench_util::batch(bench_util::SerdeAs2dPacket::new([[0; SIGNATURE_SIZE]; SIGNATURE_COUNT])))
+    }
+
+    #[cfg(all(feature = "format-bincode", feature = "strategy-serde-with"))]
+    #[bench]
+    fn bench_serialize_serde_as_bytes_rows(bencher: &mut test::Bencher) {
+        bench_util::bench_serialize(bencher, &bench_util::batch(bench_util::SerdeAsBytesRowsPacket::new([[0; SIGNATURE_SIZE]; SIGNATURE_COUNT])))
+    }
+
+    #[cfg(feature = "format-bincode")]
+    #[bench]
+    fn bench_deserialize_bytes_array_2d(bencher: &mut test::Bencher) {
+        bench_util::bench_deserialize(bencher, &bench_util::batch(Packet::default()))
+    }
+
+    #[cfg(all(feature = "format-bincode", feature = "strategy-serde-with"))]
+    #[bench]
+    fn bench_deserialize_serde_as_2d(bencher: &mut test::Bencher) {
+        bench_util::bench_deserialize(bencher, &bench_util::batch(bench_util::SerdeAs2dPacket::new([[0; SIGNATURE_SIZE]; SIGNATURE_COUNT])))
+    }
+
+    #[cfg(all(feature = "format-bincode", feature = "strategy-serde-with"))]
+    #[bench]
+    fn bench_deserialize_serde_as_bytes_rows(bencher: &mut test::Bencher) {
+        bench_util::bench_deserialize(bencher, &bench_util::batch(bench_util::SerdeAsBytesRowsPacket::new([[0; SIGNATURE_SIZE]; SIGNATURE_COUNT])))
+    }
+}
+
+#[cfg(test)]
+mod serde_bytes_array_vec_keys {
+    use super::*;
+
+    pub const KEY_SIZE: usize = 32;
+    pub const KEY_COUNT: usize = 38;
+
+    #[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+    pub struct Packet {
+        #[serde(with = "serde_bytes_array_vec")]
+        keys: Vec<[u8; KEY_SIZE]>,
+        flags: u64,
+    }
+    impl Default for Packet {
+        #[inline(always)]
+        fn default() -> Self {
+            Self {
+                keys: vec![[0; KEY_SIZE]; KEY_COUNT],
+                flags: 3,
+            }
+        }
+    }
+
+    #[cfg(all(feature = "format-bincode", feature = "format-json"))]
+    #[test]
+    fn test_bytes_array_vec_round_trip() {
+        let packet = Packet {
+            keys: (0..KEY_COUNT as u8).map(|i| [i; KEY_SIZE]).collect(),
+            flags: 3,
+        };
+        let binary = bincode::serialize(&packet).unwrap();
+        // count + byte string length prefix + keys + flags
+        assert_eq!(binary.len(), 8 + 8 + KEY_SIZE * KEY_COUNT + 8);
+        assert_eq!(bincode::deserialize::<Packet>(&binary).unwrap(), packet);
+        let mut reader = &binary[..];
+        assert_eq!(bincode::deserialize_from::<_, Packet>(&mut reader).unwrap(), packet);
+        assert_eq!(serde_json::from_str::<Packet>(&serde_json::to_string(&packet).unwrap()).unwrap(), packet);
+
+        let empty = Packet { keys: vec![], flags: 3 };
+        assert_eq!(bincode::deserialize::<Packet>(&bincode::serialize(&empty).unwrap()).unwrap(), empty);
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        struct ZeroSized(#[serde(with = "serde_bytes_array_vec")] Vec<[u8; 0]>);
+        let zero_sized = ZeroSized(vec![]);
+        assert_eq!(bincode::deserialize::<ZeroSized>(&bincode::serialize(&zero_sized).unwrap()).unwrap(), zero_sized);
+        // nothing to bound the count of zero-sized elements by, so only 0 goes either way
+        assert!(bincode::serialize(&ZeroSized(vec![[]; 3])).is_err());
+        let mut huge = u64::MAX.to_le_bytes().to_vec();
+        huge.extend_from_slice(&0u64.to_le_bytes());
+        assert!(bincode::deserialize::<ZeroSized>(&huge).is_err());
+
+        // byte-by-byte input one byte too long
+        let json = serde_json::to_string(&packet).unwrap().replacen("]]", ",0]]", 1);
+        let error = serde_json::from_str::<Packet>(&json).unwrap_err();
+        assert!(error.to_string().contains(&format!("invalid length {}", KEY_SIZE * KEY_COUNT + 1)), "{}", error);
+
+        // count says one key more than the bytes hold
+        let mut mismatched = binary.clone();
+        mismatched[..8].copy_from_slice(&(KEY_COUNT as u64 + 1).to_le_bytes());
+        assert!(bincode::deserialize::<Packet>(&mismatched).is_err());
+        mismatched[..8].copy_from_slice(&u64::MAX.to_le_bytes());
+        assert!(bincode::deserialize::<Packet>(&mismatched).is_err());
+    }
+
+    #[cfg(feature = "format-bincode")]
+    #[bench]
+    fn bench_serialize_bytes_array_vec(bencher: &mut test::Bencher) {
+        bench_util::bench_serialize(bencher, &bench_util::batch(Packet::default()))
+    }
+
+    #[cfg(all(feature = "format-bincode", feature = "strategy-serde-with", feature = "strategy-serde-bytes"))]
+    #[bench]
+    fn bench_serialize_per_element_fast_bytes(bencher: &mut test::Bencher) {
+        bench_util::bench_serialize(bencher, &bench_util::batch(bench_util::FastBytesVecPacket::new(vec![[0; KEY_SIZE]; KEY_COUNT])))
+    }
+
+    #[cfg(all(feature = "format-bincode", feature = "strategy-serde-with"))]
+    #[bench]
+    fn bench_serialize_serde_as_vec(bencher: &mut test::Bencher) {
+        bench_util::bench_serialize(bencher, &bench_util::batch(bench_util::SerdeAsVecPacket::new(vec![[0; KEY_SIZE]; KEY_COUNT])))
+    }
+
+    #[cfg(feature = "format-bincode")]
+    #[bench]
+    fn bench_deserialize_bytes_array_vec(bencher: &mut test::Bencher) {
+        bench_util::bench_deserialize(bencher, &bench_util::batch(Packet::default()))
+    }
+
+    #[cfg(all(feature = "format-bincode", feature = "strategy-serde-with", feature = "strategy-serde-bytes"))]
+    #[bench]
+    fn bench_deserialize_per_element_fast_bytes(bencher: &mut test::Bencher) {
+        bench_util::bench_deserialize(bencher, &bench_util::batch(bench_util::FastBytesVecPacket::new(vec![[0; KEY_SIZE]; KEY_COUNT])))
+    }
+
+    #[cfg(all(feature = "format-bincode", feature = "strategy-serde-with"))]
+    #[bench]
+    fn bench_deserialize_serde_as_vec(bencher: &mut test::Bencher) {
+        bench_util::bench_deserialize(bencher, &bench_util::batch(bench_util::SerdeAsVecPacket::new(vec![[0; KEY_SIZE]; KEY_COUNT])))
+    }
+}
+
+#[cfg(test)]
+mod short_vec_keys {
+    use super::*;
+
+    pub const KEY_SIZE: usize = 32;
+    pub const KEY_COUNT: usize = 38;
+
+    #[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+    pub struct Packet {
+        #[serde(with = "short_vec")]
+        keys: Vec<[u8; KEY_SIZE]>,
+        flags: u64,
+    }
+    impl Default for Packet {
+        #[inline(always)]
+        fn default() -> Self {
+            Self {
+                keys: vec![[0; KEY_SIZE]; KEY_COUNT],
+                flags: 3,
+            }
+        }
+    }
+
+    #[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+    pub struct BytesArrayPacket {
+        #[serde(with = "short_vec::bytes_array")]
+        keys: Vec<[u8; KEY_SIZE]>,
+        flags: u64,
+    }
+    impl Default for BytesArrayPacket {
+        #[inline(always)]
+        fn default() -> Self {
+            Self {
+                keys: vec![[0; KEY_SIZE]; KEY_COUNT],
+                flags: 3,
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct ShortBytes(#[serde(with = "short_vec")] Vec<u8>);
+
+    #[cfg(feature = "format-bincode")]
+    #[test]
+    fn test_short_vec_boundary_lengths() {
+        for (len, prefix) in [
+            (0, &[0x00][..]),
+            (1, &[0x01]),
+            (127, &[0x7f]),
+            (128, &[0x80, 0x01]),
+            (16383, &[0xff, 0x7f]),
+            (16384, &[0x80, 0x80, 0x01]),
+            (65535, &[0xff, 0xff, 0x03]),
+        ] {
+            let value = ShortBytes(vec![7; len]);
+            let binary = bincode::serialize(&value).unwrap();
+            assert_eq!(&binary[..prefix.len()], prefix, "len {}", len);
+            assert_eq!(binary.len(), prefix.len() + len);
+            assert_eq!(bincode::deserialize::<ShortBytes>(&binary).unwrap(), value);
+            let mut reader = &binary[..];
+            assert_eq!(bincode::deserialize_from::<_, ShortBytes>(&mut reader).unwrap(), value);
+            assert_eq!(bincode::deserialize::<short_vec::ShortU16>(prefix).unwrap(), short_vec::ShortU16(len as u16));
+        }
+
+        assert!(bincode::serialize(&ShortBytes(vec![7; 65536])).is_err());
+        // 65536 would need a fourth bit in the third byte
+        assert!(bincode::deserialize::<short_vec::ShortU16>(&[0x80, 0x80, 0x04]).is_err());
+        assert!(bincode::deserialize::<short_vec::ShortU16>(&[0xff, 0xff, 0xff]).is_err());
+        // non-shortest forms of 0 and 127
+        assert!(bincode::deserialize::<short_vec::ShortU16>(&[0x80, 0x00]).is_err());
+        assert!(bincode::deserialize::<short_vec::ShortU16>(&[0xff, 0x80, 0x00]).is_err());
+        // truncated
+        assert!(bincode::deserialize::<short_vec::ShortU16>(&[0x80]).is_err());
+        assert!(bincode::deserialize::<ShortBytes>(&[0x02, 0x07]).is_err());
+    }
+
+    #[cfg(feature = "format-bincode")]
+    #[test]
+    fn test_short_vec_bytes_array() {
+        // longer than the 32 elements serde implements arrays for
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        struct Signatures(#[serde(with = "short_vec::bytes_array")] Vec<[u8; 64]>);
+
+        let signatures = Signatures((0..128).map(|i| [i as u8; 64]).collect());
+        let binary = bincode::serialize(&signatures).unwrap();
+        assert_eq!(&binary[..2], &[0x80, 0x01]);
+        assert_eq!(binary.len(), 2 + 128 * 64);
+        assert_eq!(bincode::deserialize::<Signatures>(&binary).unwrap(), signatures);
+
+        // both flavors write the same bytes
+        let packet = Packet::default();
+        let bytes_array_packet = BytesArrayPacket::default();
+        assert_eq!(bincode::serialize(&packet).unwrap(), bincode::serialize(&bytes_array_packet).unwrap());
+        assert_eq!(
+            bincode::serialize(&packet).unwrap().len() + 7,
+            bincode::serialize(&bench_util::SerdePacket::new(vec![[0u8; KEY_SIZE]; KEY_COUNT])).unwrap().len()
+        );
+    }
+
+    #[cfg(feature = "format-bincode")]
+    #[bench]
+    fn bench_serialize_short_vec(bencher: &mut test::Bencher) {
+        bench_util::bench_serialize(bencher, &bench_util::batch(Packet::default()))
+    }
+
+    #[cfg(feature = "format-bincode")]
+    #[bench]
+    fn bench_serialize_short_vec_bytes_array(bencher: &mut test::Bencher) {
+        bench_util::bench_serialize(bencher, &bench_util::batch(BytesArrayPacket::default()))
+    }
+
+    #[cfg(feature = "format-bincode")]
+    #[bench]
+    fn bench_serialize_fixed_len(bencher: &mut test::Bencher) {
+        bench_util::bench_serialize(bencher, &bench_util::batch(bench_util::SerdePacket::new(vec![[0u8; KEY_SIZE]; KEY_COUNT])))
+    }
+
+    #[cfg(feature = "format-bincode")]
+    #[bench]
+    fn bench_deserialize_short_vec(bencher: &mut test::Bencher) {
+        bench_util::bench_deserialize(bencher, &bench_util::batch(Packet::default()))
+    }
+
+    #[cfg(feature = "format-bincode")]
+    #[bench]
+    fn bench_deserialize_short_vec_bytes_array(bencher: &mut test::Bencher) {
+        bench_util::bench_deserialize(bencher, &bench_util::batch(BytesArrayPacket::default()))
+    }
+
+    #[cfg(feature = "format-bincode")]
+    #[bench]
+    fn bench_deserialize_fixed_len(bencher: &mut test::Bencher) {
+        bench_util::bench_deserialize(bencher, &bench_util::batch(bench_util::SerdePacket::new(vec![[0u8; KEY_SIZE]; KEY_COUNT])))
+    }
+}
+
+#[cfg(test)]
+mod serde_bool_bitset_flags {
+    use super::*;
+
+    pub const FLAG_COUNT: usize = 256;
+
+    fn flags() -> [bool; FLAG_COUNT] {
+        core::array::from_fn(|i| i % 3 == 0)
+    }
+
+    #[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+    pub struct Packet {
+        #[serde(with = "serde_bool_bitset")]
+        flags: [bool; FLAG_COUNT],
+        id: u64,
+    }
+    impl Default for Packet {
+        #[inline(always)]
+        fn default() -> Self {
+            Self {
+                flags: flags(),
+                id: 3,
+            }
+        }
+    }
+
+    #[cfg(all(feature = "format-bincode", feature = "format-json"))]
+    #[test]
+    fn test_bool_bitset() {
+        let packet = Packet::default();
+        let binary = bincode::serialize(&packet).unwrap();
+        assert_eq!(binary.len(), 8 + FLAG_COUNT / 8 + 8);
+        // bits 0, 3 and 6 of the first byte
+        assert_eq!(binary[8], 0b0100_1001);
+        assert_eq!(bincode::deserialize::<Packet>(&binary).unwrap(), packet);
+        assert_eq!(serde_json::from_str::<Packet>(&serde_json::to_string(&packet).unwrap()).unwrap(), packet);
+
+        // 13 bits: the top 3 bits of the second byte are padding
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        struct Odd(#[serde(with = "serde_bool_bitset")] [bool; 13]);
+
+        let mut odd = Odd([false; 13]);
+        odd.0[12] = true;
+        let binary = bincode::serialize(&odd).unwrap();
+        assert_eq!(&binary[8..], &[0x00, 0b0001_0000]);
+        assert_eq!(bincode::deserialize::<Odd>(&binary).unwrap(), odd);
+
+        let mut dirty = binary.clone();
+        dirty[9] |= 0b0010_0000;
+        assert!(bincode::deserialize::<Odd>(&dirty).is_err());
+        assert!(bincode::deserialize::<Odd>(&[1, 0, 0, 0, 0, 0, 0, 0, 0]).is_err());
+        assert!(bincode::deserialize::<Odd>(&[3, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]).is_err());
+
+        // JSON gets a sequence: exactly two bytes, nothing missing and nothing after them
+        assert_eq!(serde_json::from_str::<Odd>("[0,16]").unwrap(), odd);
+        let short = serde_json::from_str::<Odd>("[0]").unwrap_err().to_string();
+        assert!(short.starts_with("invalid length 1,"), "{}", short);
+        let long = serde_json::from_str::<Odd>("[0,16,0]").unwrap_err().to_string();
+        assert!(long.starts_with("invalid length 3,"), "{}", long);
+
+        // too big for the stack buffer
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        struct Large(#[serde(with = "serde_bool_bitset")] [bool; 1000]);
+
+        let large = Large(core::array::from_fn(|i| i % 7 == 0));
+        let binary = bincode::serialize(&large).unwrap();
+        assert_eq!(binary.len(), 8 + 125);
+        assert_eq!(bincode::deserialize::<Large>(&binary).unwrap(), large);
+    }
+
+    #[cfg(feature = "format-bincode")]
+    #[bench]
+    fn bench_serialize_bool_bitset(bencher: &mut test::Bencher) {
+        bench_util::bench_serialize(bencher, &bench_util::batch(Packet::default()))
+    }
+
+    #[cfg(all(feature = "format-bincode", feature = "strategy-serde-with"))]
+    #[bench]
+    fn bench_serialize_serde_as_bools(bencher: &mut test::Bencher) {
+        bench_util::bench_serialize(bencher, &bench_util::batch(bench_util::SerdeAsPacket::new(flags())))
+    }
+
+    #[cfg(feature = "format-bincode")]
+    #[bench]
+    fn bench_deserialize_bool_bitset(bencher: &mut test::Bencher) {
+        bench_util::bench_deserialize(bencher, &bench_util::batch(Packet::default()))
+    }
+
+    #[cfg(all(feature = "format-bincode", feature = "strategy-serde-with"))]
+    #[bench]
+    fn bench_deserialize_serde_as_bools(bencher: &mut test::Bencher) {
+        bench_util::bench_deserialize(bencher, &bench_util::batch(bench_util::SerdeAsPacket::new(flags())))
+    }
+
+    #[cfg(feature = "format-json")]
+    #[bench]
+    fn bench_serialize_json_bool_bitset(bencher: &mut test::Bencher) {
+        bench_util::bench_serialize_json(bencher, &bench_util::batch(Packet::default()))
+    }
+
+    #[cfg(all(feature = "format-json", feature = "strategy-serde-with"))]
+    #[bench]
+    fn bench_serialize_json_serde_as_bools(bencher: &mut test::Bencher) {
+        bench_util::bench_serialize_json(bencher, &bench_util::batch(bench_util::SerdeAsPacket::new(flags())))
+    }
+
+    #[cfg(feature = "format-json")]
+    #[bench]
+    fn bench_deserialize_json_bool_bitset(bencher: &mut test::Bencher) {
+        bench_util::bench_deserialize_json(bencher, &bench_util::batch(Packet::default()))
+    }
+
+    #[cfg(all(feature = "format-json", feature = "strategy-serde-with"))]
+    #[bench]
+    fn bench_deserialize_json_serde_as_bools(bencher: &mut test::Bencher) {
+        bench_util::bench_deserialize_json(bencher, &bench_util::batch(bench_util::SerdeAsPacket::new(flags())))
+    }
+}
+
+// the same strategy matrix, but on a packet that looks like one off the wire: the array is
+// followed by a `Meta` header instead of a lone u64, so its cost can be weighed against the
+// rest of the struct
+#[cfg(feature = "packet-meta")]
+#[cfg_attr(not(any(feature = "strategy-serde-with", feature = "strategy-serde-arrays", feature = "strategy-serde-bytes")), allow(dead_code))]
+#[cfg(test)]
+mod realistic_packet {
+    use super::*;
+
+    use packet_meta::Meta;
+
+    // lets the generic benches build every variant from `payload::sized_packets()`
+    trait Sampled {
+        fn sampled(buffer: [u8; PACKET_DATA_SIZE], meta: Meta) -> Self;
+    }
+
+    // the serde_with / serde_arrays comparisons, with the header in place of bench_util's flags
+    #[cfg(feature = "strategy-serde-with")]
+    type SerdeAsPacket = bench_util::SerdeAsPacket<u8, PACKET_DATA_SIZE, Meta>;
+    #[cfg(feature = "strategy-serde-with")]
+    impl Sampled for SerdeAsPacket {
+        fn sampled(buffer: [u8; PACKET_DATA_SIZE], meta: Meta) -> Self {
+            Self::with_meta(buffer, meta)
+        }
+    }
+
+    #[cfg(feature = "strategy-serde-arrays")]
+    type SerdeArraysPacket = bench_util::SerdeArraysPacket<u8, PACKET_DATA_SIZE, Meta>;
+    #[cfg(feature = "strategy-serde-arrays")]
+    impl Sampled for SerdeArraysPacket {
+        fn sampled(buffer: [u8; PACKET_DATA_SIZE], meta: Meta) -> Self {
+            Self::with_meta(buffer, meta)
+        }
+    }
+
+    #[cfg(feature = "strategy-serde-with")]
+    type SerdeAsBytesPacket = bench_util::SerdeAsBytesPacket<PACKET_DATA_SIZE, Meta>;
+    #[cfg(feature = "strategy-serde-with")]
+    impl Sampled for SerdeAsBytesPacket {
+        fn sampled(buffer: [u8; PACKET_DATA_SIZE], meta: Meta) -> Self {
+            Self::with_meta(buffer, meta)
+        }
+    }
+
+    #[cfg(feature = "strategy-serde-bytes")]
+    #[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+    pub struct SerdeBytesPacket {
+        #[serde(with = "serde_bytes_array")]
+        buffer: [u8; PACKET_DATA_SIZE],
+        meta: Meta,
+    }
+    #[cfg(feature = "strategy-serde-bytes")]
+    impl Default for SerdeBytesPacket {
+        #[inline(always)]
+        fn default() -> Self {
+            Self {
+                buffer: [0; PACKET_DATA_SIZE],
+                meta: Meta::synthetic(PACKET_DATA_SIZE),
+            }
+        }
+    }
+    #[cfg(feature = "strategy-serde-bytes")]
+    impl Sampled for SerdeBytesPacket {
+        fn sampled(buffer: [u8; PACKET_DATA_SIZE], meta: Meta) -> Self {
+            Self { buffer, meta }
+        }
+    }
+
+    #[cfg(feature = "strategy-serde-bytes")]
+    #[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+    pub struct FixedBytesPacket {
+        buffer: fixed_bytes::FixedBytes<PACKET_DATA_SIZE>,
+        meta: Meta,
+    }
+    #[cfg(feature = "strategy-serde-bytes")]
+    impl Default for FixedBytesPacket {
+        #[inline(always)]
+        fn default() -> Self {
+            Self {
+                buffer: fixed_bytes::FixedBytes::default(),
+                meta: Meta::synthetic(PACKET_DATA_SIZE),
+            }
+        }
+    }
+    #[cfg(feature = "strategy-serde-bytes")]
+    impl Sampled for FixedBytesPacket {
+        fn sampled(buffer: [u8; PACKET_DATA_SIZE], meta: Meta) -> Self {
+            Self {
+                buffer: buffer.into(),
+                meta,
+            }
+        }
+    }
+
+    #[cfg(feature = "strategy-serde-bytes")]
+    #[serde_array_derive::fast_array_serde]
+    #[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+    pub struct FastArraySerdePacket {
+        buffer: [u8; PACKET_DATA_SIZE],
+        meta: Meta,
+    }
+    #[cfg(feature = "strategy-serde-bytes")]
+    impl Default for FastArraySerdePacket {
+        #[inline(always)]
+        fn default() -> Self {
+            Self {
+                buffer: [0; PACKET_DATA_SIZE],
+                meta: Meta::synthetic(PACKET_DATA_SIZE),
+            }
+        }
+    }
+    #[cfg(feature = "strategy-serde-bytes")]
+    impl Sampled for FastArraySerdePacket {
+        fn sampled(buffer: [u8; PACKET_DATA_SIZE], meta: Meta) -> Self {
+            Self { buffer, meta }
+        }
+    }
+
+    #[cfg(all(feature = "format-bincode", feature = "format-json", feature = "strategy-serde-bytes"))]
+    #[test]
+    fn test_realistic_packet() {
+        let packet = SerdeBytesPacket::default();
+        let binary = bincode::serialize(&packet).unwrap();
+        // length prefix + buffer, then size, IpAddr variant + 4 octets, port and flags
+        assert_eq!(binary.len(), 8 + PACKET_DATA_SIZE + 8 + 4 + 4 + 2 + 1);
+        assert_eq!(bincode::deserialize::<SerdeBytesPacket>(&binary).unwrap(), packet);
+
+        let json = serde_json::to_string(&packet).unwrap();
+        assert!(json.ends_with(r#""meta":{"size":1232,"addr":"10.0.0.1","port":8001,"flags":"FORWARDED | SIMPLE_VOTE_TX"}}"#));
+        assert_eq!(serde_json::from_str::<SerdeBytesPacket>(&json).unwrap(), packet);
+
+        // every strategy carries the same header
+        assert_eq!(bincode::serialize(&FixedBytesPacket::default()).unwrap(), binary);
+        assert_eq!(bincode::serialize(&FastArraySerdePacket::default()).unwrap(), binary);
+    }
+
+    #[cfg(all(feature = "format-bincode", feature = "strategy-serde-with"))]
+    #[bench]
+    fn bench_serialize_serde_as_realistic(bencher: &mut test::Bencher) {
+        bench_util::bench_serialize(bencher, &payload::sized_packets(512, SerdeAsPacket::sampled))
+    }
+
+    #[cfg(all(feature = "format-bincode", feature = "strategy-serde-arrays"))]
+    #[bench]
+    fn bench_serialize_serde_arrays_realistic(bencher: &mut test::Bencher) {
+        bench_util::bench_serialize(bencher, &payload::sized_packets(512, SerdeArraysPacket::sampled))
+    }
+
+    #[cfg(all(feature = "format-bincode", feature = "strategy-serde-with"))]
+    #[bench]
+    fn bench_serialize_serde_as_bytes_realistic(bencher: &mut test::Bencher) {
+        bench_util::bench_serialize(bencher, &payload::sized_packets(512, SerdeAsBytesPacket::sampled))
+    }
+
+    #[cfg(all(feature = "format-bincode", feature = "strategy-serde-bytes"))]
+    #[bench]
+    fn bench_serialize_serde_bytes_realistic(bencher: &mut test::Bencher) {
+        bench_util::bench_serialize(bencher, &payload::sized_packets(512, SerdeBytesPacket::sampled))
+    }
+
+    #[cfg(all(feature = "format-bincode", feature = "strategy-serde-bytes"))]
+    #[bench]
+    fn bench_serialize_fixed_bytes_realistic(bencher: &mut test::Bencher) {
+        bench_util::bench_serialize(bencher, &payload::sized_packets(512, FixedBytesPacket::sampled))
+    }
+
+    #[cfg(all(feature = "format-bincode", feature = "strategy-serde-bytes"))]
+    #[bench]
+    fn bench_serialize_fast_array_serde_realistic(bencher: &mut test::Bencher) {
+        bench_util::bench_serialize(bencher, &payload::sized_packets(512, FastArraySerdePacket::sampled))
+    }
+
+    #[cfg(all(feature = "format-bincode", feature = "strategy-serde-with"))]
+    #[bench]
+    fn bench_deserialize_serde_as_realistic(bencher: &mut test::Bencher) {
+        bench_util::bench_deserialize(bencher, &payload::sized_packets(512, SerdeAsPacket::sampled))
+    }
+
+    #[cfg(all(feature = "format-bincode", feature = "strategy-serde-arrays"))]
+    #[bench]
+    fn bench_deserialize_serde_arrays_realistic(bencher: &mut test::Bencher) {
+        bench_util::bench_deserialize(bencher, &payload::sized_packets(512, SerdeArraysPacket::sampled))
+    }
+
+    #[cfg(all(feature = "format-bincode", feature = "strategy-serde-with"))]
+    #[bench]
+    fn bench_deserialize_serde_as_bytes_realistic(bencher: &mut test::Bencher) {
+        bench_util::bench_deserialize(bencher, &payload::sized_packets(512, SerdeAsBytesPacket::sampled))
+    }
+
+    #[cfg(all(feature = "format-bincode", feature = "strategy-serde-bytes"))]
+    #[bench]
+    fn bench_deserialize_serde_bytes_realistic(bencher: &mut test::Bencher) {
+        bench_util::bench_deserialize(bencher, &payload::sized_packets(512, SerdeBytesPacket::sampled))
+    }
+
+    #[cfg(all(feature = "format-bincode", feature = "strategy-serde-bytes"))]
+    #[bench]
+    fn bench_deserialize_fixed_bytes_realistic(bencher: &mut test::Bencher) {
+        bench_util::bench_deserialize(bencher, &payload::sized_packets(512, FixedBytesPacket::sampled))
+    }
+
+    #[cfg(all(feature = "format-bincode", feature = "strategy-serde-bytes"))]
+    #[bench]
+    fn bench_deserialize_fast_array_serde_realistic(bencher: &mut test::Bencher) {
+        bench_util::bench_deserialize(bencher, &payload::sized_packets(512, FastArraySerdePacket::sampled))
+    }
+
+    #[cfg(all(feature = "format-json", feature = "strategy-serde-with"))]
+    #[bench]
+    fn bench_serialize_json_serde_as_realistic(bencher: &mut test::Bencher) {
+        bench_util::bench_serialize_json(bencher, &payload::sized_packets(512, SerdeAsPacket::sampled))
+    }
+
+    #[cfg(all(feature = "format-json", feature = "strategy-serde-arrays"))]
+    #[bench]
+    fn bench_serialize_json_serde_arrays_realistic(bencher: &mut test::Bencher) {
+        bench_util::bench_serialize_json(bencher, &payload::sized_packets(512, SerdeArraysPacket::sampled))
+    }
+
+    #[cfg(all(feature = "format-json", feature = "strategy-serde-with"))]
+    #[bench]
+    fn bench_serialize_json_serde_as_bytes_realistic(bencher: &mut test::Bencher) {
+        bench_util::bench_serialize_json(bencher, &payload::sized_packets(512, SerdeAsBytesPacket::sampled))
+    }
+
+    #[cfg(all(feature = "format-json", feature = "strategy-serde-bytes"))]
+    #[bench]
+    fn bench_serialize_json_serde_bytes_realistic(bencher: &mut test::Bencher) {
+        bench_util::bench_serialize_json(bencher, &payload::sized_packets(512, SerdeBytesPacket::sampled))
+    }
+
+    #[cfg(all(feature = "format-json", feature = "strategy-serde-bytes"))]
+    #[bench]
+    fn bench_serialize_json_fixed_bytes_realistic(bencher: &mut test::Bencher) {
+        bench_util::bench_serialize_json(bencher, &payload::sized_packets(512, FixedBytesPacket::sampled))
+    }
+
+    #[cfg(all(feature = "format-json", feature = "strategy-serde-bytes"))]
+    #[bench]
+    fn bench_serialize_json_fast_array_serde_realistic(bencher: &mut test::Bencher) {
+        bench_util::bench_serialize_json(bencher, &payload::sized_packets(512, FastArraySerdePacket::sampled))
+    }
+
+    #[cfg(all(feature = "format-json", feature = "strategy-serde-with"))]
+    #[bench]
+    fn bench_deserialize_json_serde_as_realistic(bencher: &mut test::Bencher) {
+        bench_util::bench_deserialize_json(bencher, &payload::sized_packets(512, SerdeAsPacket::sampled))
+    }
+
+    #[cfg(all(feature = "format-json", feature = "strategy-serde-arrays"))]
+    #[bench]
+    fn bench_deserialize_json_serde_arrays_realistic(bencher: &mut test::Bencher) {
+        bench_util::bench_deserialize_json(bencher, &payload::sized_packets(512, SerdeArraysPacket::sampled))
+    }
+
+    #[cfg(all(feature = "format-json", feature = "strategy-serde-with"))]
+    #[bench]
+    fn bench_deserialize_json_serde_as_bytes_realistic(bencher: &mut test::Bencher) {
+        bench_util::bench_deserialize_json(bencher, &payload::sized_packets(512, SerdeAsBytesPacket::sampled))
+    }
+
+    #[cfg(all(feature = "format-json", feature = "strategy-serde-bytes"))]
+    #[bench]
+    fn bench_deserialize_json_serde_bytes_realistic(bencher: &mut test::Bencher) {
+        bench_util::bench_deserialize_json(bencher, &payload::sized_packets(512, SerdeBytesPacket::sampled))
+    }
+
+    #[cfg(all(feature = "format-json", feature = "strategy-serde-bytes"))]
+    #[bench]
+    fn bench_deserialize_json_fixed_bytes_realistic(bencher: &mut test::Bencher) {
+        bench_util::bench_deserialize_json(bencher, &payload::sized_packets(512, FixedBytesPacket::sampled))
+    }
+
+    #[cfg(all(feature = "format-json", feature = "strategy-serde-bytes"))]
+    #[bench]
+    fn bench_deserialize_json_fast_array_serde_realistic(bencher: &mut test::Bencher) {
+        bench_util::bench_deserialize_json(bencher, &payload::sized_packets(512, FastArraySerdePacket::sampled))
+    }
+}
+
+// only the used prefix of each packet goes out, measured on a few made-up but plausible
+// length mixes: mostly votes, a mix of votes and transfers with the odd full packet, and all full
+#[cfg(test)]
+mod sized_buffer_lengths {
+    use super::*;
+
+    use sized_buffer::SizedBuffer;
+
+    #[derive(Clone, Default, Serialize, Deserialize, Debug, PartialEq)]
+    pub struct Packet {
+        buffer: SizedBuffer<PACKET_DATA_SIZE>,
+        flags: u64,
+    }
+
+    // the same packets with the whole array on the wire, for comparison
+    #[cfg(feature = "strategy-serde-bytes")]
+    #[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+    pub struct FullPacket {
+        #[serde(with = "serde_bytes_array")]
+        buffer: [u8; PACKET_DATA_SIZE],
+        flags: u64,
+    }
+
+    #[derive(Clone, Copy)]
+    enum Lengths {
+        Votes,
+        Mixed,
+        Full,
+    }
+
+    // fixed seed rather than BENCH_SEED: the length mix is what these benches are about
+    fn lengths(kind: Lengths, count: usize) -> Vec<usize> {
+        let mut rng = payload::Rng::new(payload::DEFAULT_SEED);
+        let mut next = move |range: core::ops::RangeInclusive<usize>| range.start() + rng.up_to(range.end() - range.start());
+        (0..count)
+            .map(|_| match kind {
+                Lengths::Votes => next(160..=260),
+                Lengths::Mixed => match next(0..=99) {
+                    0..=59 => next(160..=260),
+                    60..=94 => next(260..=1000),
+                    _ => PACKET_DATA_SIZE,
+                },
+                Lengths::Full => PACKET_DATA_SIZE,
+            })
+            .collect()
+    }
+
+    fn packets(kind: Lengths) -> Vec<Packet> {
+        lengths(kind, 512)
+            .into_iter()
+            .map(|len| {
+                let mut buffer = SizedBuffer::default();
+                buffer.as_array_mut()[..len].fill(7);
+                buffer.set_len(len);
+                Packet { buffer, flags: 3 }
+            })
+            .collect()
+    }
+
+    #[cfg(feature = "strategy-serde-bytes")]
+    fn full_packets(kind: Lengths) -> Vec<FullPacket> {
+        packets(kind)
+            .into_iter()
+            .map(|packet| {
+                let mut buffer = [0; PACKET_DATA_SIZE];
+                buffer[..packet.buffer.len()].copy_from_slice(&packet.buffer);
+                FullPacket { buffer, flags: packet.flags }
+            })
+            .collect()
+    }
+
+    #[cfg(feature = "format-bincode")]
+    #[test]
+    fn test_sized_buffer() {
+        let packets = packets(Lengths::Mixed);
+        let binary = bincode::serialize(&packets).unwrap();
+        let used: usize = packets.iter().map(|packet| packet.buffer.len()).sum();
+        assert_eq!(binary.len(), 8 + packets.len() * (8 + 8) + used);
+        assert_eq!(bincode::deserialize::<Vec<Packet>>(&binary).unwrap(), packets);
+
+        // bytes past the used prefix are neither written nor read back
+        let mut dirty = SizedBuffer::<4>::from_slice(&[1, 2]).unwrap();
+        dirty.as_array_mut()[3] = 9;
+        let binary = bincode::serialize(&dirty).unwrap();
+        assert_eq!(binary, [2, 0, 0, 0, 0, 0, 0, 0, 1, 2]);
+        let mut read = bincode::deserialize::<SizedBuffer<4>>(&binary).unwrap();
+        assert_eq!(read, dirty);
+        assert_eq!(read.as_array_mut(), &[1, 2, 0, 0]);
+
+        assert!(bincode::deserialize::<SizedBuffer<4>>(&[5, 0, 0, 0, 0, 0, 0, 0, 1, 2, 3, 4, 5]).is_err());
+        assert!(SizedBuffer::<4>::from_slice(&[0; 5]).is_none());
+        assert!(std::panic::catch_unwind(|| SizedBuffer::<4>::default().set_len(5)).is_err());
+    }
+
+    #[cfg(feature = "format-json")]
+    #[test]
+    fn test_sized_buffer_json() {
+        let buffer = SizedBuffer::<4>::from_slice(&[1, 2, 3]).unwrap();
+        let json = serde_json::to_string(&buffer).unwrap();
+        assert_eq!(json, "[1,2,3]");
+        assert_eq!(serde_json::from_str::<SizedBuffer<4>>(&json).unwrap(), buffer);
+        assert!(serde_json::from_str::<SizedBuffer<4>>("[1,2,3,4,5]").is_err());
+    }
+
+    #[cfg(feature = "format-bincode")]
+    #[bench]
+    fn bench_serialize_sized_buffer_votes(bencher: &mut test::Bencher) {
+        bench_util::bench_serialize(bencher, &packets(Lengths::Votes))
+    }
+
+    #[cfg(all(feature = "format-bincode", feature = "strategy-serde-bytes"))]
+    #[bench]
+    fn bench_serialize_full_array_votes(bencher: &mut test::Bencher) {
+        bench_util::bench_serialize(bencher, &full_packets(Lengths::Votes))
+    }
+
+    #[cfg(feature = "format-bincode")]
+    #[bench]
+    fn bench_serialize_sized_buffer_mixed(bencher: &mut test::Bencher) {
+        bench_util::bench_serialize(bencher, &packets(Lengths::Mixed))
+    }
+
+    #[cfg(all(feature = "format-bincode", feature = "strategy-serde-bytes"))]
+    #[bench]
+    fn bench_serialize_full_array_mixed(bencher: &mut test::Bencher) {
+        bench_util::bench_serialize(bencher, &full_packets(Lengths::Mixed))
+    }
+
+    #[cfg(feature = "format-bincode")]
+    #[bench]
+    fn bench_serialize_sized_buffer_full(bencher: &mut test::Bencher) {
+        bench_util::bench_serialize(bencher, &packets(Lengths::Full))
+    }
+
+    #[cfg(all(feature = "format-bincode", feature = "strategy-serde-bytes"))]
+    #[bench]
+    fn bench_serialize_full_array_full(bencher: &mut test::Bencher) {
+        bench_util::bench_serialize(bencher, &full_packets(Lengths::Full))
+    }
+
+    #[cfg(feature = "format-bincode")]
+    #[bench]
+    fn bench_deserialize_sized_buffer_votes(bencher: &mut test::Bencher) {
+        bench_util::bench_deserialize(bencher, &packets(Lengths::Votes))
+    }
+
+    #[cfg(all(feature = "format-bincode", feature = "strategy-serde-bytes"))]
+    #[bench]
+    fn bench_deserialize_full_array_votes(bencher: &mut test::Bencher) {
+        bench_util::bench_deserialize(bencher, &full_packets(Lengths::Votes))
+    }
+
+    #[cfg(feature = "format-bincode")]
+    #[bench]
+    fn bench_deserialize_sized_buffer_mixed(bencher: &mut test::Bencher) {
+        bench_util::bench_deserialize(bencher, &packets(Lengths::Mixed))
+    }
+
+    #[cfg(all(feature = "format-bincode", feature = "strategy-serde-bytes"))]
+    #[bench]
+    fn bench_deserialize_full_array_mixed(bencher: &mut test::Bencher) {
+        bench_util::bench_deserialize(bencher, &full_packets(Lengths::Mixed))
+    }
+
+    #[cfg(feature = "format-bincode")]
+    #[bench]
+    fn bench_deserialize_sized_buffer_full(bencher: &mut test::Bencher) {
+        bench_util::bench_deserialize(bencher, &packets(Lengths::Full))
+    }
+
+    #[cfg(all(feature = "format-bincode", feature = "strategy-serde-bytes"))]
+    #[bench]
+    fn bench_deserialize_full_array_full(bencher: &mut test::Bencher) {
+        bench_util::bench_deserialize(bencher, &full_packets(Lengths::Full))
+    }
+}
+
+// `pcap::read*()` against the synthetic captures in fixtures/: the same five frames (UDP over IPv4,
+// TCP, UDP over IPv6, an oversized VLAN-tagged UDP datagram and a UDP fragment) in both formats,
+// written by fixtures/generate.py.
+// to run the benches on a capture: BENCH_PAYLOAD=pcap BENCH_PCAP=fixtures/udp.pcapng cargo bench
+#[cfg(test)]
+mod pcap_fixtures {
+    use {
+        super::*,
+        std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    };
+
+    pub const PCAP: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/udp.pcap");
+    pub const PCAPNG: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/udp.pcapng");
+
+    fn expected() -> Vec<pcap::Datagram> {
+        let a = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
+        let b = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 2));
+        let v6 = |last| IpAddr::V6(Ipv6Addr::new(0xfe80, 0, 0, 0, 0, 0, 0, last));
+        vec![
+            pcap::Datagram {
+                source: SocketAddr::new(a, 8001),
+                destination: SocketAddr::new(b, 8002),
+                payload: b"hello".to_vec(),
+            },
+            pcap::Datagram {
+                source: SocketAddr::new(v6(1), 9000),
+                destination: SocketAddr::new(v6(2), 9001),
+                payload: (0..200).collect(),
+            },
+            pcap::Datagram {
+                source: SocketAddr::new(b, 8003),
+                destination: SocketAddr::new(a, 8001),
+                payload: vec![7; 1300],
+            },
+        ]
+    }
+
+    #[test]
+    fn test_read_pcap() {
+        assert_eq!(pcap::read_file(PCAP).unwrap(), expected());
+    }
+
+    #[test]
+    fn test_read_pcapng() {
+        assert_eq!(pcap::read_file(PCAPNG).unwrap(), expected());
+    }
+
+    #[test]
+    fn test_read_malformed() {
+        let capture = std::fs::read(PCAP).unwrap();
+        assert!(pcap::read(&capture[..capture.len() - 1]).is_err());
+        assert!(pcap::read(&capture[..3]).is_err());
+        assert!(pcap::read(b"not a capture at all").is_err());
+        // no records at all is fine
+        assert_eq!(pcap::read(&capture[..24]).unwrap(), []);
+
+        let capture = std::fs::read(PCAPNG).unwrap();
+        assert!(pcap::read(&capture[..capture.len() - 4]).is_err());
+    }
+}
+
+// `PacketStream` against decoding the whole `Vec<Packet>`, at the usual 512 packets and at 1M,
+// where the `Vec` alone is 1.2 GiB and the stream still holds one packet at a time.
+// those two take a while even for the single run `cargo test` gives them, so they're ignored
+// by default: cargo bench -- --ignored million
+#[cfg(all(feature = "format-bincode", feature = "strategy-serde-bytes"))]
+#[cfg(test)]
+mod packet_stream_batches {
+    use {
+        super::*,
+        std::io::{self, Read},
+    };
+
+    pub const MILLION: u64 = 1_000_000;
+
+    #[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+    pub struct Packet {
+        #[serde(with = "serde_bytes_array")]
+        buffer: [u8; PACKET_DATA_SIZE],
+        flags: u64,
+    }
+
+    fn packets(count: usize) -> Vec<Packet> {
+        payload::packets(count, |buffer| Packet { buffer, flags: 3 })
+    }
+
+    // the bincode encoding of `count` copies of one packet, made up on the fly so that the 1M
+    // benches don't need 1.2 GiB of input either
+    fn repeated(packet: &Packet, count: u64) -> impl Read {
+        struct Repeat {
+            packet: Vec<u8>,
+            offset: u64,
+            end: u64,
+        }
+
+        impl Read for Repeat {
+            fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+                let start = (self.offset % self.packet.len() as u64) as usize;
+                let len = buf.len().min(self.packet.len() - start).min((self.end - self.offset) as usize);
+                buf[..len].copy_from_slice(&self.packet[start..start + len]);
+                self.offset += len as u64;
+                Ok(len)
+            }
+        }
+
+        let packet = bincode::serialize(packet).unwrap();
+        let end = packet.len() as u64 * count;
+        io::Cursor::new(count.to_le_bytes()).chain(Repeat { packet, offset: 0, end })
+    }
+
+    #[test]
+    fn test_packet_stream() {
+        let packets = packets(16);
+        let mut binary = bincode::serialize(&packets).unwrap();
+        binary.extend_from_slice(b"rest");
+
+        let mut stream = packet_stream::PacketStream::<_, Packet>::new(&binary[..]).unwrap();
+        assert_eq!(stream.size_hint(), (16, Some(16)));
+        assert_eq!(stream.next().unwrap().unwrap(), packets[0]);
+        assert_eq!(stream.remaining(), 15);
+        let rest: Vec<_> = stream.by_ref().collect::<bincode::Result<_>>().unwrap();
+        assert_eq!(rest, packets[1..]);
+        assert!(stream.next().is_none());
+        assert_eq!(stream.into_inner(), b"rest");
+
+        // a truncated batch errors once, then stops
+        let truncated = &binary[..binary.len() - 4 - 100];
+        let results: Vec<_> = packet_stream::PacketStream::<_, Packet>::new(truncated).unwrap().collect();
+        assert_eq!(results.len(), 16);
+        assert!(results[..15].iter().all(Result::is_ok));
+        assert!(results[15].is_err());
+        assert!(packet_stream::PacketStream::<_, Packet>::new(&binary[..7]).is_err());
+
+        // the made-up input decodes like the real thing
+        let mut binary = vec![];
+        repeated(&packets[0], 3).read_to_end(&mut binary).unwrap();
+        assert_eq!(binary, bincode::serialize(&vec![packets[0].clone(); 3]).unwrap());
+    }
+
+    #[bench]
+    fn bench_deserialize_vec_512(bencher: &mut test::Bencher) {
+        let s = bincode::serialize(&packets(512)).unwrap();
+
+        bencher.iter(|| {
+            for packet in bincode::deserialize_from::<_, Vec<Packet>>(&s[..]).unwrap() {
+                test::black_box(packet.flags);
+            }
+        })
+    }
+
+    #[bench]
+    fn bench_deserialize_stream_512(bencher: &mut test::Bencher) {
+        let s = bincode::serialize(&packets(512)).unwrap();
+
+        bencher.iter(|| {
+            for packet in packet_stream::PacketStream::<_, Packet>::new(&s[..]).unwrap() {
+                test::black_box(packet.unwrap().flags);
+            }
+        })
+    }
+
+    #[bench]
+    #[ignore]
+    fn bench_deserialize_vec_million(bencher: &mut test::Bencher) {
+        let packet = packets(1).remove(0);
+
+        bencher.iter(|| {
+            for packet in bincode::deserialize_from::<_, Vec<Packet>>(repeated(&packet, MILLION)).unwrap() {
+                test::black_box(packet.flags);
+            }
+        })
+    }
+
+    #[bench]
+    #[ignore]
+    fn bench_deserialize_stream_million(bencher: &mut test::Bencher) {
+        let packet = packets(1).remove(0);
+
+        bencher.iter(|| {
+            for packet in packet_stream::PacketStream::<_, Packet>::new(repeated(&packet, MILLION)).unwrap() {
+                test::black_box(packet.unwrap().flags);
+            }
+        })
+    }
+}
+
+// serializing packets as they're produced against collecting them into `input_packets` first
+#[cfg(all(feature = "format-bincode", feature = "strategy-serde-bytes"))]
+#[cfg(test)]
+mod seq_iter_batches {
+    use super::*;
+
+    #[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+    pub struct Packet {
+        #[serde(with = "serde_bytes_array")]
+        buffer: [u8; PACKET_DATA_SIZE],
+        flags: u64,
+    }
+
+    // stands in for whatever produces packets lazily, e.g. a receive loop
+    fn produce(templates: &[[u8; PACKET_DATA_SIZE]]) -> impl ExactSizeIterator<Item = Packet> + '_ {
+        templates.iter().enumerate().map(|(i, buffer)| Packet {
+            buffer: *buffer,
+            flags: i as u64,
+        })
+    }
+
+    fn templates() -> Vec<[u8; PACKET_DATA_SIZE]> {
+        payload::packets(512, |buffer| buffer)
+    }
+
+    // claims one more item than it has
+    struct Liar<I>(I);
+
+    impl<I: ExactSizeIterator> Iterator for Liar<I> {
+        type Item = I::Item;
+
+        fn next(&mut self) -> Option<Self::Item> {
+            self.0.next()
+        }
+
+        fn size_hint(&self) -> (usize, Option<usize>) {
+            (self.0.len() + 1, Some(self.0.len() + 1))
+        }
+    }
+
+    impl<I: ExactSizeIterator> ExactSizeIterator for Liar<I> {}
+
+    #[test]
+    fn test_seq_iter() {
+        let templates = templates();
+        let packets: Vec<_> = produce(&templates).collect();
+        let expected = bincode::serialize(&packets).unwrap();
+
+        let mut binary = vec![];
+        serde_seq_iter::serialize_into(&mut binary, packets.iter()).unwrap();
+        assert_eq!(binary, expected);
+        binary.clear();
+        serde_seq_iter::serialize_into(&mut binary, produce(&templates)).unwrap();
+        assert_eq!(binary, expected);
+        assert_eq!(bincode::serialize(&serde_seq_iter::SeqIter::new(&packets)).unwrap(), expected);
+        assert_eq!(bincode::deserialize::<Vec<Packet>>(&binary).unwrap(), packets);
+
+        #[cfg(feature = "format-json")]
+        assert_eq!(
+            serde_json::to_string(&serde_seq_iter::SeqIter::new(packets.iter().take(3))).unwrap(),
+            serde_json::to_string(&packets[..3]).unwrap()
+        );
+
+        assert!(serde_seq_iter::serialize_into(vec![], Liar(packets.iter())).is_err());
+    }
+
+    #[bench]
+    fn bench_serialize_collect(bencher: &mut test::Bencher) {
+        let templates = templates();
+        let mut output_binary = vec![];
+
+        bencher.iter(|| {
+            let input_packets: Vec<_> = produce(&templates).collect();
+            bincode::serialize_into(&mut output_binary, &input_packets).unwrap();
+            test::black_box(&output_binary);
+            output_binary.clear();
+        })
+    }
+
+    #[bench]
+    fn bench_serialize_seq_iter(bencher: &mut test::Bencher) {
+        let templates = templates();
+        let mut output_binary = vec![];
+
+        bencher.iter(|| {
+            serde_seq_iter::serialize_into(&mut output_binary, produce(&templates)).unwrap();
+            test::black_box(&output_binary);
+            output_binary.clear();
+        })
+    }
+
+    // already collected: the iterator over references costs nothing over `&Vec`
+    #[bench]
+    fn bench_serialize_vec(bencher: &mut test::Bencher) {
+        let input_packets: Vec<_> = produce(&templates()).collect();
+        let mut output_binary = vec![];
+
+        bencher.iter(|| {
+            bincode::serialize_into(&mut output_binary, &input_packets).unwrap();
+            test::black_box(&output_binary);
+            output_binary.clear();
+        })
+    }
+
+    #[bench]
+    fn bench_serialize_seq_iter_refs(bencher: &mut test::Bencher) {
+        let input_packets: Vec<_> = produce(&templates()).collect();
+        let mut output_binary = vec![];
+
+        bencher.iter(|| {
+            serde_seq_iter::serialize_into(&mut output_binary, input_packets.iter()).unwrap();
+            test::black_box(&output_binary);
+            output_binary.clear();
+        })
+    }
+}
+
+// reading a few packets out of a serialized batch through `PacketBatchView` against deserializing
+// all of it first
+#[cfg(all(feature = "format-bincode", feature = "strategy-serde-bytes"))]
+#[cfg(test)]
+mod packet_batch_view_access {
+    use super::*;
+
+    #[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+    pub struct Packet {
+        #[serde(with = "serde_bytes_array")]
+        buffer: [u8; PACKET_DATA_SIZE],
+        flags: u64,
+    }
+
+    fn batch() -> Vec<u8> {
+        let mut flags = 0;
+        let packets = payload::packets(512, |buffer| {
+            flags += 1;
+            Packet { buffer, flags }
+        });
+        bincode::serialize(&packets).unwrap()
+    }
+
+    fn indices() -> Vec<usize> {
+        let mut rng = payload::Rng::new(payload::DEFAULT_SEED);
+        (0..16).map(|_| rng.up_to(511)).collect()
+    }
+
+    #[test]
+    fn test_packet_batch_view() {
+        let s = batch();
+        let packets = bincode::deserialize::<Vec<Packet>>(&s).unwrap();
+        let view = packet_batch_view::PacketBatchView::<PACKET_DATA_SIZE>::new(&s).unwrap();
+        assert_eq!(view.len(), 512);
+        assert!(view.iter().zip(&packets).all(|(view, packet)| *view.buffer == packet.buffer && view.flags == packet.flags));
+        assert_eq!(view.get(511).unwrap().flags, 512);
+        assert!(view.get(512).is_none());
+        assert!(view.get(usize::MAX).is_none());
+
+        // the same layout however the array gets there
+        let derived = bincode::serialize(&vec![fast_array_serde_attribute::Packet::default()]).unwrap();
+        assert_eq!(packet_batch_view::PacketBatchView::<PACKET_DATA_SIZE>::new(&derived).unwrap().get(0).unwrap().flags, 3);
+
+        let mut trailing = s.clone();
+        trailing.push(0);
+        assert_eq!(packet_batch_view::PacketBatchView::<PACKET_DATA_SIZE>::new(&trailing).unwrap().len(), 512);
+        assert!(packet_batch_view::PacketBatchView::<PACKET_DATA_SIZE>::new(&s[..s.len() - 1]).is_err());
+        assert!(packet_batch_view::PacketBatchView::<PACKET_DATA_SIZE>::new(&s[..7]).is_err());
+        assert!(packet_batch_view::PacketBatchView::<PACKET_DATA_SIZE>::new(&u64::MAX.to_le_bytes()).is_err());
+        assert!(packet_batch_view::PacketBatchView::<32>::new(&s).is_err());
+        let mut bad = s.clone();
+        bad[8 + 3 * packet_batch_view::PacketBatchView::<PACKET_DATA_SIZE>::RECORD_LEN] = 0;
+        assert!(packet_batch_view::PacketBatchView::<PACKET_DATA_SIZE>::new(&bad).is_err());
+        assert!(packet_batch_view::PacketBatchView::<PACKET_DATA_SIZE>::new(&[0; 8]).unwrap().is_empty());
+    }
+
+    #[bench]
+    fn bench_random_access_view(bencher: &mut test::Bencher) {
+        let s = batch();
+        let indices = indices();
+
+        bencher.iter(|| {
+            let view = packet_batch_view::PacketBatchView::<PACKET_DATA_SIZE>::new(&s).unwrap();
+            for &i in &indices {
+                let packet = view.get(i).unwrap();
+                test::black_box((packet.buffer[0], packet.flags));
+            }
+        })
+    }
+
+    #[bench]
+    fn bench_random_access_deserialize(bencher: &mut test::Bencher) {
+        let s = batch();
+        let indices = indices();
+
+        bencher.iter(|| {
+            let packets = bincode::deserialize::<Vec<Packet>>(&s).unwrap();
+            for &i in &indices {
+                test::black_box((packets[i].buffer[0], packets[i].flags));
+            }
+        })
+    }
+
+    // validation already done, just the lookups
+    #[bench]
+    fn bench_random_access_view_get(bencher: &mut test::Bencher) {
+        let s = batch();
+        let indices = indices();
+        let view = packet_batch_view::PacketBatchView::<PACKET_DATA_SIZE>::new(&s).unwrap();
+
+        bencher.iter(|| {
+            for &i in &indices {
+                let packet = view.get(i).unwrap();
+                test::black_box((packet.buffer[0], packet.flags));
+            }
+        })
+    }
+}
+
+// `EncodedLen` for each fixed-layout strategy, checked against what bincode actually writes
+#[cfg(feature = "format-bincode")]
+#[cfg(test)]
+mod encoded_len_packets {
+    use {
+        super::*,
+        encoded_len::{batch, EncodedLen},
+    };
+
+    #[cfg(any(feature = "strategy-serde-with", feature = "strategy-serde-bytes"))]
+    use encoded_len::byte_string;
+
+    #[cfg(feature = "strategy-serde-with")]
+    type SerdeAsPacket = bench_util::SerdeAsPacket<u8, PACKET_DATA_SIZE>;
+
+    #[cfg(feature = "strategy-serde-with")]
+    impl<T: EncodedLen, const N: usize, M: EncodedLen> EncodedLen for bench_util::SerdeAsPacket<T, N, M> {
+        const ENCODED_LEN: usize = <[T; N]>::ENCODED_LEN + M::ENCODED_LEN;
+    }
+
+    #[cfg(feature = "strategy-serde-arrays")]
+    type SerdeArraysPacket = bench_util::SerdeArraysPacket<u8, PACKET_DATA_SIZE>;
+
+    #[cfg(feature = "strategy-serde-arrays")]
+    impl<T: EncodedLen, const N: usize, M: EncodedLen> EncodedLen for bench_util::SerdeArraysPacket<T, N, M> {
+        const ENCODED_LEN: usize = <[T; N]>::ENCODED_LEN + M::ENCODED_LEN;
+    }
+
+    #[cfg(feature = "strategy-serde-with")]
+    impl EncodedLen for serde_as_bytes::Packet {
+        const ENCODED_LEN: usize = byte_string(PACKET_DATA_SIZE) + u64::ENCODED_LEN;
+    }
+
+    #[cfg(feature = "strategy-serde-bytes")]
+    impl EncodedLen for serde_bytes_slice::Packet {
+        const ENCODED_LEN: usize = byte_string(PACKET_DATA_SIZE) + u64::ENCODED_LEN;
+    }
+
+    #[cfg(feature = "strategy-serde-bytes")]
+    impl EncodedLen for fixed_bytes_newtype::Packet {
+        const ENCODED_LEN: usize = fixed_bytes::FixedBytes::<PACKET_DATA_SIZE>::ENCODED_LEN + u64::ENCODED_LEN;
+    }
+
+    #[cfg(feature = "strategy-serde-bytes")]
+    impl EncodedLen for fast_array_serde_attribute::Packet {
+        const ENCODED_LEN: usize = byte_string(PACKET_DATA_SIZE) + u64::ENCODED_LEN;
+    }
+
+    #[cfg_attr(not(any(feature = "strategy-serde-with", feature = "strategy-serde-arrays", feature = "strategy-serde-bytes")), allow(dead_code))]
+    fn check<T: EncodedLen + Clone + serde::Serialize>(packet: T) {
+        assert_eq!(bincode::serialized_size(&packet).unwrap(), T::ENCODED_LEN as u64);
+        let packets = vec![packet; 512];
+        assert_eq!(bincode::serialized_size(&packets).unwrap(), batch::<T>(512) as u64);
+        assert_eq!(bincode::serialize(&packets).unwrap().len(), batch::<T>(512));
+    }
+
+    #[test]
+    fn test_encoded_len() {
+        assert_eq!(<[u16; 3]>::ENCODED_LEN, bincode::serialized_size(&[1u16, 2, 3]).unwrap() as usize);
+
+        #[cfg(feature = "strategy-serde-with")]
+        {
+            check(SerdeAsPacket::new([0; PACKET_DATA_SIZE]));
+            check(serde_as_bytes::Packet::default());
+            assert_eq!(SerdeAsPacket::ENCODED_LEN, PACKET_DATA_SIZE + 8);
+            assert_eq!(serde_as_bytes::Packet::ENCODED_LEN, 8 + PACKET_DATA_SIZE + 8);
+        }
+        #[cfg(feature = "strategy-serde-arrays")]
+        check(SerdeArraysPacket::with_meta([0; PACKET_DATA_SIZE], 3));
+        #[cfg(feature = "strategy-serde-bytes")]
+        {
+            check(serde_bytes_slice::Packet::default());
+            check(fixed_bytes_newtype::Packet::default());
+            check(fast_array_serde_attribute::Packet::default());
+            assert_eq!(
+                packet_batch_view::PacketBatchView::<PACKET_DATA_SIZE>::RECORD_LEN,
+                serde_bytes_slice::Packet::ENCODED_LEN
+            );
+        }
+    }
+
+    // record i at `8 + i * ENCODED_LEN`, flags in its last 8 bytes
+    #[cfg(feature = "strategy-serde-with")]
+    #[test]
+    fn test_offsets() {
+        let packets: Vec<_> = (0..16).map(|flags| SerdeAsPacket::with_meta([0; PACKET_DATA_SIZE], flags)).collect();
+        let binary = bincode::serialize(&packets).unwrap();
+        for (i, packet) in packets.iter().enumerate() {
+            let end = batch::<SerdeAsPacket>(i + 1);
+            assert_eq!(u64::from_le_bytes(binary[end - 8..end].try_into().unwrap()), packet.meta);
+        }
+    }
+
+    // what exact sizing saves over letting the output `Vec` grow, both starting from nothing.
+    // byte strings, so the copying doesn't drown it out like per-element tuple encoding would
+    #[cfg(feature = "strategy-serde-bytes")]
+    #[bench]
+    fn bench_serialize_presized(bencher: &mut test::Bencher) {
+        let input_packets = payload::packets(512, |buffer| serde_bytes_slice::Packet { buffer, flags: 3 });
+
+        bencher.iter(|| {
+            let mut output_binary = Vec::with_capacity(batch::<serde_bytes_slice::Packet>(input_packets.len()));
+            bincode::serialize_into(&mut output_binary, &input_packets).unwrap();
+            test::black_box(&output_binary);
+        })
+    }
+
+    #[cfg(feature = "strategy-serde-bytes")]
+    #[bench]
+    fn bench_serialize_growing(bencher: &mut test::Bencher) {
+        let input_packets = payload::packets(512, |buffer| serde_bytes_slice::Packet { buffer, flags: 3 });
+
+        bencher.iter(|| {
+            let mut output_binary = vec![];
+            bincode::serialize_into(&mut output_binary, &input_packets).unwrap();
+            test::black_box(&output_binary);
+        })
+    }
+}
+
+// decoding into the same `Vec<Packet>` every time against a fresh one per decode, like the other
+// deserialize benches do
+#[cfg(all(feature = "format-bincode", feature = "strategy-serde-bytes"))]
+#[cfg(test)]
+mod vec_in_place_packets {
+    use {
+        super::*,
+        core::fmt,
+        serde::de::{DeserializeSeed, Deserializer, Error, IgnoredAny, MapAccess, SeqAccess, Visitor},
+        serde_bytes_slice::Packet,
+        serde_vec_in_place::DeserializeInPlace,
+    };
+
+    // by hand what derive would generate, with `buffer` written in place. the shared adapter rather
+    // than the packet's own, which can only borrow and so can't read JSON
+    impl<'de> DeserializeInPlace<'de> for Packet {
+        #[inline(always)]
+        fn deserialize_into<D>(deserializer: D, place: &mut Self) -> Result<(), D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            deserializer.deserialize_struct("Packet", &["buffer", "flags"], PacketVisitor(place))
+        }
+    }
+
+    struct BufferSeed<'a>(&'a mut [u8; PACKET_DATA_SIZE]);
+
+    impl<'de> DeserializeSeed<'de> for BufferSeed<'_> {
+        type Value = ();
+
+        #[inline(always)]
+        fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            serde_bytes_array::deserialize_in_place(deserializer, self.0)
+        }
+    }
+
+    #[derive(serde_derive::Deserialize)]
+    #[serde(field_identifier, rename_all = "lowercase")]
+    enum Field {
+        Buffer,
+        Flags,
+        #[serde(other)]
+        Other,
+    }
+
+    struct PacketVisitor<'a>(&'a mut Packet);
+
+    impl<'de> Visitor<'de> for PacketVisitor<'_> {
+        type Value = ();
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("struct Packet")
+        }
+
+        #[inline(always)]
+        fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+        where
+            A: SeqAccess<'de>,
+        {
+            seq.next_element_seed(BufferSeed(&mut self.0.buffer))?
+                .ok_or_else(|| A::Error::invalid_length(0, &self))?;
+            self.0.flags = seq.next_element()?.ok_or_else(|| A::Error::invalid_length(1, &self))?;
+            Ok(())
+        }
+
+        fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+        where
+            A: MapAccess<'de>,
+        {
+            let (mut buffer, mut flags) = (false, false);
+            while let Some(field) = map.next_key()? {
+                match field {
+                    Field::Buffer => {
+                        map.next_value_seed(BufferSeed(&mut self.0.buffer))?;
+                        buffer = true;
+                    }
+                    Field::Flags => {
+                        self.0.flags = map.next_value()?;
+                        flags = true;
+                    }
+                    Field::Other => {
+                        map.next_value::<IgnoredAny>()?;
+                    }
+                }
+            }
+            if !buffer {
+                return Err(A::Error::missing_field("buffer"));
+            }
+            if !flags {
+                return Err(A::Error::missing_field("flags"));
+            }
+            Ok(())
+        }
+    }
+
+    fn packets(count: usize) -> Vec<Packet> {
+        let mut flags = 0;
+        payload::packets(count, |buffer| {
+            flags += 1;
+            Packet { buffer, flags }
+        })
+    }
+
+    #[test]
+    fn test_vec_in_place() {
+        let packets = packets(16);
+        let s = bincode::serialize(&packets).unwrap();
+
+        // same length: nothing allocated, everything overwritten
+        let mut stored = vec![Packet { buffer: [0xff; PACKET_DATA_SIZE], flags: 0 }; 16];
+        let allocation = stored.as_ptr();
+        serde_vec_in_place::deserialize_into_vec(&s, &mut stored).unwrap();
+        assert_eq!(stored, packets);
+        assert_eq!(stored.as_ptr(), allocation);
+
+        let mut shorter = vec![Packet::default(); 4];
+        serde_vec_in_place::deserialize_into_vec(&s, &mut shorter).unwrap();
+        assert_eq!(shorter, packets);
+
+        let mut longer = vec![Packet::default(); 20];
+        let capacity = longer.capacity();
+        serde_vec_in_place::deserialize_into_vec(&s, &mut longer).unwrap();
+        assert_eq!(longer, packets);
+        assert_eq!(longer.capacity(), capacity);
+
+        assert!(serde_vec_in_place::deserialize_into_vec(&s[..s.len() - 1], &mut longer).is_err());
 
-        bencher.iter(|| {
-            test::black_box(bincode::serialize_into(&mut output_binary, &input_packets).unwrap());
-            output_binary.clear();
-        })
+        // a length prefix far beyond the input only reserves a bounded amount before running out
+        let mut huge = u64::MAX.to_le_bytes().to_vec();
+        huge.extend_from_slice(&s[8..]);
+        let mut empty: Vec<Packet> = vec![];
+        assert!(serde_vec_in_place::deserialize_into_vec(&huge, &mut empty).is_err());
+        assert!(empty.capacity() * core::mem::size_of::<Packet>() <= 1024 * 1024);
+
+        #[cfg(feature = "format-json")]
+        {
+            let json = serde_json::to_string(&packets[..3]).unwrap();
+            let mut stored = vec![Packet::default(); 3];
+            serde_vec_in_place::VecInPlace(&mut stored)
+                .deserialize(&mut serde_json::Deserializer::from_str(&json))
+                .unwrap();
+            assert_eq!(stored, packets[..3]);
+            // unknown fields are skipped, as the derived `Deserialize` does
+            let mut extra = json.replacen(r#""flags":"#, r#""extra":[0,{"a":1}],"flags":"#, 1);
+            extra.insert_str(extra.len() - 2, r#","other":null"#);
+            let mut stored = vec![Packet::default(); 3];
+            serde_vec_in_place::VecInPlace(&mut stored)
+                .deserialize(&mut serde_json::Deserializer::from_str(&extra))
+                .unwrap();
+            assert_eq!(stored, packets[..3]);
+            let mut incomplete = serde_json::Deserializer::from_str(r#"[{"flags":1}]"#);
+            assert!(serde_vec_in_place::VecInPlace(&mut stored).deserialize(&mut incomplete).is_err());
+        }
     }
 
     #[bench]
-    fn bench_deserialize_serde_bytes_normal(bencher: &mut test::Bencher) {
-        use std::io::Write;
-        let mut input_packets: Vec<_> = std::iter::repeat(Packet::default()).take(512).collect();
-        let mut stream = std::io::BufWriter::new(std::fs::OpenOptions::new().append(true).create(true).open("./out").unwrap());
-        test::black_box(bincode::serialize_into(&mut stream, &input_packets).unwrap());
-        stream.flush();
-
-        let mut stream = std::io::BufReader::new(std::fs::File::open("./out").unwrap());
-        let mut s: Vec<u8> = vec![];
-        use std::io::Read;
-        stream.read_to_end(&mut s).unwrap();
+    fn bench_deserialize_new_vec(bencher: &mut test::Bencher) {
+        let s = bincode::serialize(&packets(512)).unwrap();
 
         bencher.iter(|| {
             test::black_box(bincode::deserialize::<Vec<Packet>>(&s).unwrap());
@@ -212,260 +5837,154 @@ mod serde_as_bytes {
     }
 
     #[bench]
-    fn bench_deserialize_from_serde_bytes_normal(bencher: &mut test::Bencher) {
-        use std::io::Write;
-        let mut input_packets: Vec<_> = std::iter::repeat(Packet::default()).take(512).collect();
-        let mut stream = std::io::BufWriter::new(std::fs::OpenOptions::new().append(true).create(true).open("./out").unwrap());
-        test::black_box(bincode::serialize_into(&mut stream, &input_packets).unwrap());
-        stream.flush();
-
-        let mut stream = std::io::BufReader::new(std::fs::File::open("./out").unwrap());
-        let mut s: Vec<u8> = vec![];
-        use std::io::Read;
-        stream.read_to_end(&mut s).unwrap();
+    fn bench_deserialize_in_place(bencher: &mut test::Bencher) {
+        let s = bincode::serialize(&packets(512)).unwrap();
+        let mut stored: Vec<Packet> = Vec::with_capacity(512);
 
         bencher.iter(|| {
-            let mut reader = &s[..];
-            bincode::deserialize_from::<_, Vec<Packet>>(&mut reader).unwrap();
+            serde_vec_in_place::deserialize_into_vec(&s, &mut stored).unwrap();
+            test::black_box(&stored);
         })
     }
 }
 
-
-mod serde_bytes_vec {
+#[cfg(all(feature = "format-json", feature = "strategy-serde-bytes"))]
+#[cfg(test)]
+mod json_parsers {
     use super::*;
 
-    mod serde_bytes_array {
-        use {
-            core::convert::TryInto,
-            serde::{de::Error, Deserializer, Serializer},
-        };
-
-        #[inline(always)]
-        pub(crate) fn serialize<S>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error>
-        where
-            S: Serializer,
-        {
-            serde_bytes::serialize(bytes, serializer)
-        }
-
-        #[inline(always)]
-        pub(crate) fn deserialize<'de, D, const N: usize>(deserializer: D) -> Result<[u8; N], D::Error>
-        where
-            D: Deserializer<'de>,
-        {
-            let vec: Vec<u8> = serde_bytes::deserialize(deserializer)?;
-            let vec_len = vec.len();
-            let array: [u8; N] = vec.try_into().map_err(|_| {
-                let expected = format!("[u8; {}]", N);
-                D::Error::invalid_length(vec_len, &expected.as_str())
-            })?;
-            Ok(array)
-        }
+    fn hex_packets() -> Vec<serde_bytes_human_readable::Packet> {
+        payload::packets(512, |buffer| serde_bytes_human_readable::Packet { buffer, flags: 3 })
     }
 
-    #[derive(Clone, Serialize, Deserialize, Debug)]
-    pub struct Packet {
-        #[serde(with = "serde_bytes_array")]
-        buffer: [u8; PACKET_DATA_SIZE],
-        flags: u64,
+    fn base64_packets() -> Vec<serde_bytes_human_readable::Base64Packet> {
+        payload::packets(512, |buffer| serde_bytes_human_readable::Base64Packet { buffer, flags: 3 })
     }
-    impl Default for Packet {
-        #[inline(always)]
-        fn default() -> Self {
-            Self {
-                buffer: [0; PACKET_DATA_SIZE],
-                flags: 3,
-            }
-        }
+
+    #[cfg(feature = "strategy-serde-with")]
+    fn number_array_packets() -> Vec<serde_bytes_human_readable::NumberArrayPacket> {
+        payload::packets(512, |buffer| serde_bytes_human_readable::NumberArrayPacket { buffer, flags: 3 })
     }
 
-    #[bench]
-    fn bench_serialize_serde_bytes_normal(bencher: &mut test::Bencher) {
-        let mut output_binary = vec![];
-        let mut input_packets: Vec<_> = std::iter::repeat(Packet::default()).take(512).collect();
+    fn bench_from_slice<T: serde::Serialize + serde::de::DeserializeOwned>(bencher: &mut test::Bencher, input_packets: &[T]) {
+        let s = serde_json::to_vec(input_packets).unwrap();
 
         bencher.iter(|| {
-            test::black_box(bincode::serialize_into(&mut output_binary, &input_packets).unwrap());
-            output_binary.clear();
+            test::black_box(serde_json::from_slice::<Vec<T>>(&s).unwrap());
         })
     }
 
-    #[bench]
-    fn bench_deserialize_serde_bytes_normal(bencher: &mut test::Bencher) {
-        use std::io::Write;
-        let mut input_packets: Vec<_> = std::iter::repeat(Packet::default()).take(512).collect();
-        let mut stream = std::io::BufWriter::new(std::fs::OpenOptions::new().append(true).create(true).open("./out").unwrap());
-        test::black_box(bincode::serialize_into(&mut stream, &input_packets).unwrap());
-        stream.flush();
-
-        let mut stream = std::io::BufReader::new(std::fs::File::open("./out").unwrap());
-        let mut s: Vec<u8> = vec![];
-        use std::io::Read;
-        stream.read_to_end(&mut s).unwrap();
+    fn bench_from_reader<T: serde::Serialize + serde::de::DeserializeOwned>(bencher: &mut test::Bencher, input_packets: &[T]) {
+        let s = serde_json::to_vec(input_packets).unwrap();
 
         bencher.iter(|| {
-            test::black_box(bincode::deserialize::<Vec<Packet>>(&s).unwrap());
+            let mut reader = &s[..];
+            test::black_box(serde_json::from_reader::<_, Vec<T>>(&mut reader).unwrap());
         })
     }
 
-    #[bench]
-    fn bench_deserialize_from_serde_bytes_normal(bencher: &mut test::Bencher) {
-        use std::io::Write;
-        let mut input_packets: Vec<_> = std::iter::repeat(Packet::default()).take(512).collect();
-        let mut stream = std::io::BufWriter::new(std::fs::OpenOptions::new().append(true).create(true).open("./out").unwrap());
-        test::black_box(bincode::serialize_into(&mut stream, &input_packets).unwrap());
-        stream.flush();
-
-        let mut stream = std::io::BufReader::new(std::fs::File::open("./out").unwrap());
-        let mut s: Vec<u8> = vec![];
-        use std::io::Read;
-        stream.read_to_end(&mut s).unwrap();
+    fn bench_from_str<T: serde::Serialize + serde::de::DeserializeOwned>(bencher: &mut test::Bencher, input_packets: &[T]) {
+        let s = String::from_utf8(serde_json::to_vec(input_packets).unwrap()).unwrap();
 
         bencher.iter(|| {
-            let mut reader = &s[..];
-            bincode::deserialize_from::<_, Vec<Packet>>(&mut reader).unwrap();
+            test::black_box(serde_json::from_str::<Vec<T>>(&s).unwrap());
         })
     }
-}
 
-mod serde_bytes_cow {
-    use super::*;
+    // simd-json parses in place, so every iteration also pays for copying the input into scratch
+    #[cfg(feature = "format-simd-json")]
+    fn bench_simd_json<T: serde::Serialize + serde::de::DeserializeOwned>(bencher: &mut test::Bencher, input_packets: &[T]) {
+        let s = serde_json::to_vec(input_packets).unwrap();
+        let mut scratch = Vec::with_capacity(s.len());
 
-    mod serde_bytes_array {
-        use {
-            core::convert::TryInto,
-            serde::{de::Error, Deserializer, Serializer},
-        };
+        bencher.iter(|| {
+            scratch.clear();
+            scratch.extend_from_slice(&s);
+            test::black_box(simd_json::serde::from_slice::<Vec<T>>(&mut scratch).unwrap());
+        })
+    }
 
-        #[inline(always)]
-        pub(crate) fn serialize<S>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error>
-        where
-            S: Serializer,
-        {
-            serde_bytes::serialize(bytes, serializer)
-        }
+    #[cfg(all(feature = "strategy-serde-with", feature = "strategy-serde-bytes"))]
+    #[bench]
+    fn bench_deserialize_from_slice_number_array_normal(bencher: &mut test::Bencher) {
+        bench_from_slice(bencher, &number_array_packets())
+    }
 
-        #[inline(always)]
-        pub(crate) fn deserialize<'de, D, const N: usize>(deserializer: D) -> Result<[u8; N], D::Error>
-        where
-            D: Deserializer<'de>,
-        {
-            let slice: std::borrow::Cow<'de, [u8]> = serde_bytes::deserialize(deserializer)?;
-            //dbg!(&slice);
-            let array: [u8; N] = (&*slice).try_into().map_err(|_| {
-                let expected = format!("[u8; {}]", N);
-                D::Error::invalid_length(slice.len(), &expected.as_str())
-            })?;
-            Ok(array)
-        }
+    #[cfg(all(feature = "strategy-serde-with", feature = "strategy-serde-bytes"))]
+    #[bench]
+    fn bench_deserialize_from_reader_number_array_normal(bencher: &mut test::Bencher) {
+        bench_from_reader(bencher, &number_array_packets())
     }
 
-    #[derive(Clone, Serialize, Deserialize, Debug)]
-    pub struct Packet {
-        #[serde(with = "serde_bytes_array")]
-        buffer: [u8; PACKET_DATA_SIZE],
-        flags: u64,
+    #[cfg(all(feature = "strategy-serde-with", feature = "strategy-serde-bytes"))]
+    #[bench]
+    fn bench_deserialize_from_str_number_array_normal(bencher: &mut test::Bencher) {
+        bench_from_str(bencher, &number_array_packets())
     }
-    impl Default for Packet {
-        #[inline(always)]
-        fn default() -> Self {
-            Self {
-                buffer: [0; PACKET_DATA_SIZE],
-                flags: 3,
-            }
-        }
+
+    #[cfg(all(feature = "format-simd-json", feature = "strategy-serde-with", feature = "strategy-serde-bytes"))]
+    #[bench]
+    fn bench_deserialize_simd_json_number_array_normal(bencher: &mut test::Bencher) {
+        bench_simd_json(bencher, &number_array_packets())
     }
 
+    #[cfg(feature = "strategy-serde-bytes")]
     #[bench]
-    fn bench_serialize_serde_bytes_normal(bencher: &mut test::Bencher) {
-        let mut output_binary = vec![];
-        let mut input_packets: Vec<_> = std::iter::repeat(Packet::default()).take(512).collect();
+    fn bench_deserialize_from_slice_hex_normal(bencher: &mut test::Bencher) {
+        bench_from_slice(bencher, &hex_packets())
+    }
 
-        bencher.iter(|| {
-            test::black_box(bincode::serialize_into(&mut output_binary, &input_packets).unwrap());
-            output_binary.clear();
-        })
+    #[cfg(feature = "strategy-serde-bytes")]
+    #[bench]
+    fn bench_deserialize_from_reader_hex_normal(bencher: &mut test::Bencher) {
+        bench_from_reader(bencher, &hex_packets())
     }
 
+    #[cfg(feature = "strategy-serde-bytes")]
     #[bench]
-    fn bench_deserialize_serde_bytes_normal(bencher: &mut test::Bencher) {
-        use std::io::Write;
-        let mut input_packets: Vec<_> = std::iter::repeat(Packet::default()).take(512).collect();
-        let mut stream = std::io::BufWriter::new(std::fs::OpenOptions::new().append(true).create(true).open("./out").unwrap());
-        test::black_box(bincode::serialize_into(&mut stream, &input_packets).unwrap());
-        stream.flush();
+    fn bench_deserialize_from_str_hex_normal(bencher: &mut test::Bencher) {
+        bench_from_str(bencher, &hex_packets())
+    }
 
-        let mut stream = std::io::BufReader::new(std::fs::File::open("./out").unwrap());
-        let mut s: Vec<u8> = vec![];
-        use std::io::Read;
-        stream.read_to_end(&mut s).unwrap();
+    #[cfg(all(feature = "format-simd-json", feature = "strategy-serde-bytes"))]
+    #[bench]
+    fn bench_deserialize_simd_json_hex_normal(bencher: &mut test::Bencher) {
+        bench_simd_json(bencher, &hex_packets())
+    }
 
-        bencher.iter(|| {
-            //let mut reader = &s[..];
-            //bincode::deserialize_from::<_, Vec<Packet>>(&mut stream).unwrap();
-            //test::black_box(bincode::deserialize_from::<_, Vec<Packet>>(&mut stream).unwrap());
-            //bincode::deserialize_from::<_, Vec<Packet>>(&mut stream).unwrap();
-            test::black_box(bincode::deserialize::<Vec<Packet>>(&s).unwrap());
-            //bincode::deserialize::<Vec<Packet>>(&s).unwrap();
-        })
+    #[cfg(feature = "strategy-serde-bytes")]
+    #[bench]
+    fn bench_deserialize_from_slice_base64_normal(bencher: &mut test::Bencher) {
+        bench_from_slice(bencher, &base64_packets())
     }
 
+    #[cfg(feature = "strategy-serde-bytes")]
     #[bench]
-    fn bench_deserialize_from_serde_bytes_normal(bencher: &mut test::Bencher) {
-        use std::io::Write;
-        let mut input_packets: Vec<_> = std::iter::repeat(Packet::default()).take(512).collect();
-        let mut stream = std::io::BufWriter::new(std::fs::OpenOptions::new().append(true).create(true).open("./out").unwrap());
-        test::black_box(bincode::serialize_into(&mut stream, &input_packets).unwrap());
-        stream.flush();
+    fn bench_deserialize_from_reader_base64_normal(bencher: &mut test::Bencher) {
+        bench_from_reader(bencher, &base64_packets())
+    }
 
-        let mut stream = std::io::BufReader::new(std::fs::File::open("./out").unwrap());
-        let mut s: Vec<u8> = vec![];
-        use std::io::Read;
-        stream.read_to_end(&mut s).unwrap();
+    #[cfg(feature = "strategy-serde-bytes")]
+    #[bench]
+    fn bench_deserialize_from_str_base64_normal(bencher: &mut test::Bencher) {
+        bench_from_str(bencher, &base64_packets())
+    }
 
-        bencher.iter(|| {
-            let mut reader = &s[..];
-            bincode::deserialize_from::<_, Vec<Packet>>(&mut reader).unwrap();
-        })
+    #[cfg(all(feature = "format-simd-json", feature = "strategy-serde-bytes"))]
+    #[bench]
+    fn bench_deserialize_simd_json_base64_normal(bencher: &mut test::Bencher) {
+        bench_simd_json(bencher, &base64_packets())
     }
 }
 
-mod serde_bytes_slice {
+#[cfg(feature = "format-rkyv")]
+#[cfg(test)]
+mod rkyv_archived {
     use super::*;
 
-    mod serde_bytes_array {
-        use {
-            core::convert::TryInto,
-            serde::{de::Error, Deserializer, Serializer},
-        };
-
-        #[inline(always)]
-        pub(crate) fn serialize<S>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error>
-        where
-            S: Serializer,
-        {
-            serde_bytes::serialize(bytes, serializer)
-        }
-
-        #[inline(always)]
-        pub(crate) fn deserialize<'de, D, const N: usize>(deserializer: D) -> Result<[u8; N], D::Error>
-        where
-            D: Deserializer<'de>,
-        {
-            let slice: &[u8] = serde_bytes::deserialize(deserializer)?;
-            //dbg!(&slice);
-            let array: [u8; N] = slice.try_into().map_err(|_| {
-                let expected = format!("[u8; {}]", N);
-                D::Error::invalid_length(slice.len(), &expected.as_str())
-            })?;
-            Ok(array)
-        }
-    }
-
-    #[derive(Clone, Serialize, Deserialize, Debug)]
+    #[derive(Clone, rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)]
+    #[archive(check_bytes)]
     pub struct Packet {
-        #[serde(with = "serde_bytes_array")]
         buffer: [u8; PACKET_DATA_SIZE],
         flags: u64,
     }
@@ -480,140 +5999,209 @@ mod serde_bytes_slice {
     }
 
     #[bench]
-    fn bench_serialize_serde_bytes_normal(bencher: &mut test::Bencher) {
-        let mut output_binary = vec![];
-        let mut input_packets: Vec<_> = std::iter::repeat(Packet::default()).take(512).collect();
+    fn bench_serialize_rkyv_normal(bencher: &mut test::Bencher) {
+        let input_packets = payload::packets(512, |buffer| Packet { buffer, ..Packet::default() });
 
         bencher.iter(|| {
-            test::black_box(bincode::serialize_into(&mut output_binary, &input_packets).unwrap());
-            output_binary.clear();
+            test::black_box(rkyv::to_bytes::<_, 4096>(&input_packets).unwrap());
         })
     }
 
+    // zero-copy access never materializes a Packet, so touch buffer and flags of each one
+    // like serde_bytes_slice::bench_access_serde_bytes_normal does after deserializing
     #[bench]
-    fn bench_deserialize_serde_bytes_normal(bencher: &mut test::Bencher) {
-        use std::io::Write;
-        let mut input_packets: Vec<_> = std::iter::repeat(Packet::default()).take(512).collect();
-        let mut stream = std::io::BufWriter::new(std::fs::OpenOptions::new().append(true).create(true).open("./out").unwrap());
-        test::black_box(bincode::serialize_into(&mut stream, &input_packets).unwrap());
-        stream.flush();
-
-        let mut stream = std::io::BufReader::new(std::fs::File::open("./out").unwrap());
-        let mut s: Vec<u8> = vec![];
-        use std::io::Read;
-        stream.read_to_end(&mut s).unwrap();
+    fn bench_access_rkyv_validated_normal(bencher: &mut test::Bencher) {
+        let input_packets = payload::packets(512, |buffer| Packet { buffer, ..Packet::default() });
+        let s = rkyv::to_bytes::<_, 4096>(&input_packets).unwrap();
 
         bencher.iter(|| {
-            test::black_box(bincode::deserialize::<Vec<Packet>>(&s).unwrap());
+            let packets = rkyv::check_archived_root::<Vec<Packet>>(&s[..]).unwrap();
+            test::black_box(packets.iter().map(|p| p.buffer[0] as u64 + p.flags).sum::<u64>());
         })
     }
 
     #[bench]
-    fn bench_deserialize_from_serde_bytes_normal(bencher: &mut test::Bencher) {
-        use std::io::Write;
-        let mut input_packets: Vec<_> = std::iter::repeat(Packet::default()).take(512).collect();
-        let mut stream = std::io::BufWriter::new(std::fs::OpenOptions::new().append(true).create(true).open("./out").unwrap());
-        test::black_box(bincode::serialize_into(&mut stream, &input_packets).unwrap());
-        stream.flush();
+    fn bench_access_rkyv_unvalidated_normal(bencher: &mut test::Bencher) {
+        let input_packets = payload::packets(512, |buffer| Packet { buffer, ..Packet::default() });
+        let s = rkyv::to_bytes::<_, 4096>(&input_packets).unwrap();
+
+        bencher.iter(|| {
+            // SAFETY: `s` was produced by `rkyv::to_bytes()` above for the same type
+            let packets = unsafe { rkyv::archived_root::<Vec<Packet>>(&s[..]) };
+            test::black_box(packets.iter().map(|p| p.buffer[0] as u64 + p.flags).sum::<u64>());
+        })
+    }
 
-        let mut stream = std::io::BufReader::new(std::fs::File::open("./out").unwrap());
-        let mut s: Vec<u8> = vec![];
-        use std::io::Read;
-        stream.read_to_end(&mut s).unwrap();
+    #[bench]
+    fn bench_deserialize_rkyv_normal(bencher: &mut test::Bencher) {
+        use rkyv::Deserialize;
+        let input_packets = payload::packets(512, |buffer| Packet { buffer, ..Packet::default() });
+        let s = rkyv::to_bytes::<_, 4096>(&input_packets).unwrap();
 
         bencher.iter(|| {
-            let mut reader = &s[..];
-            bincode::deserialize_from::<_, Vec<Packet>>(&mut reader).unwrap();
+            let packets = rkyv::check_archived_root::<Vec<Packet>>(&s[..]).unwrap();
+            let packets: Vec<Packet> = packets.deserialize(&mut rkyv::Infallible).unwrap();
+            test::black_box(packets);
         })
     }
 }
 
-mod serde_bytes_slice_json {
-    use super::*;
+// `serde-array export-pcap <out.pcap> [--count <n>] [--bincode <file>]`
+//
+// writes packets as UDP datagrams to a loopback destination, to look at bench data in Wireshark or
+// feed it back in with BENCH_PAYLOAD=pcap. by default the packets come from the payload generators
+// (BENCH_PAYLOAD etc. apply, 512 of them); with --bincode they're decoded from a bincode
+// `Vec<Packet>` of `buffer` and `flags`
+#[cfg(feature = "format-bincode")]
+mod export_pcap {
+    use {
+        super::*,
+        sized_buffer::SizedBuffer,
+        std::{
+            io,
+            net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+        },
+    };
 
-    mod serde_bytes_array {
-        use {
-            core::convert::TryInto,
-            serde::{de::Error, Deserializer, Serializer},
-        };
+    pub const DESTINATION_PORT: u16 = 8000;
 
-        #[inline(always)]
-        pub(crate) fn serialize<S>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error>
-        where
-            S: Serializer,
-        {
-            serde_bytes::serialize(bytes, serializer)
-        }
+    // reads the serde_bytes encoding of a `[u8; PACKET_DATA_SIZE]` as well, since that's the same
+    // length-prefixed byte string in bincode
+    #[derive(Clone, Default, Serialize, Deserialize, Debug, PartialEq)]
+    pub struct Packet {
+        buffer: SizedBuffer<PACKET_DATA_SIZE>,
+        flags: u64,
+    }
 
-        #[inline(always)]
-        pub(crate) fn deserialize<'de, D, const N: usize>(deserializer: D) -> Result<[u8; N], D::Error>
-        where
-            D: Deserializer<'de>,
-        {
-            let slice: &[u8] = serde_bytes::deserialize(deserializer)?;
-            //dbg!(&slice);
-            let array: [u8; N] = slice.try_into().map_err(|_| {
-                let expected = format!("[u8; {}]", N);
-                D::Error::invalid_length(slice.len(), &expected.as_str())
-            })?;
-            Ok(array)
+    pub fn datagram(payload: &[u8], size: usize, source: SocketAddr) -> pcap::Datagram {
+        let loopback = match source.ip() {
+            IpAddr::V4(_) => IpAddr::V4(Ipv4Addr::LOCALHOST),
+            IpAddr::V6(_) => IpAddr::V6(Ipv6Addr::LOCALHOST),
+        };
+        pcap::Datagram {
+            source,
+            destination: SocketAddr::new(loopback, DESTINATION_PORT),
+            // a size read back from somewhere else may claim more than the buffer holds
+            payload: payload[..size.min(payload.len())].to_vec(),
         }
     }
 
-    #[derive(Clone, Serialize, Deserialize, Debug)]
-    pub struct Packet {
-        #[serde(with = "serde_bytes_array")]
-        buffer: [u8; PACKET_DATA_SIZE],
-        flags: u64,
+    pub fn decode(bytes: &[u8]) -> io::Result<Vec<pcap::Datagram>> {
+        let packets: Vec<Packet> = bincode::deserialize(bytes).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        Ok(packets
+            .iter()
+            .map(|packet| datagram(&packet.buffer, packet.buffer.len(), payload::SYNTHETIC_SOURCE))
+            .collect())
     }
-    impl Default for Packet {
-        #[inline(always)]
-        fn default() -> Self {
-            Self {
-                buffer: [0; PACKET_DATA_SIZE],
-                flags: 3,
+
+    pub fn generate(count: usize) -> Vec<pcap::Datagram> {
+        payload::sourced_packets::<PACKET_DATA_SIZE, _>(count, |buffer, size, source| datagram(&buffer, size, source))
+    }
+
+    pub fn run(args: &[String]) -> io::Result<()> {
+        let usage = || io::Error::new(io::ErrorKind::InvalidInput, "usage: export-pcap <out.pcap> [--count <n>] [--bincode <file>]");
+
+        let mut output = None;
+        let mut count = 512;
+        let mut bincode_file = None;
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--count" => count = args.next().and_then(|n| n.parse().ok()).ok_or_else(usage)?,
+                "--bincode" => bincode_file = Some(args.next().ok_or_else(usage)?),
+                _ if output.is_none() && !arg.starts_with("--") => output = Some(arg),
+                _ => return Err(usage()),
             }
         }
+        let output = output.ok_or_else(usage)?;
+
+        let datagrams = match bincode_file {
+            #[cfg(feature = "format-bincode")]
+            Some(path) => decode(&std::fs::read(path)?)?,
+            None => generate(count),
+        };
+        pcap::write(io::BufWriter::new(std::fs::File::create(output)?), &datagrams)?;
+        eprintln!("wrote {} datagrams to {}", datagrams.len(), output);
+        Ok(())
     }
 
-    #[bench]
-    fn bench_deserialize_serde_bytes_normal(bencher: &mut test::Bencher) {
-        use std::io::Write;
-        let mut input_packets: Vec<_> = std::iter::repeat(Packet::default()).take(512).collect();
-        let mut stream = std::io::BufWriter::new(std::fs::OpenOptions::new().append(true).create(true).open("./out").unwrap());
-        test::black_box(serde_json::to_writer(&mut stream, &input_packets).unwrap());
-        stream.flush();
+    #[test]
+    fn test_round_trip() {
+        let mut datagrams = generate(16);
+        datagrams.push(datagram(&[9; 100], 40, SocketAddr::new(IpAddr::V6(Ipv6Addr::new(0xfe80, 0, 0, 0, 0, 0, 0, 1)), 9000)));
+        let mut capture = vec![];
+        pcap::write(&mut capture, &datagrams).unwrap();
+        assert_eq!(capture[16..20], 262_144u32.to_le_bytes());
+        assert_eq!(pcap::read(&capture).unwrap(), datagrams);
 
-        let mut stream = std::io::BufReader::new(std::fs::File::open("./out").unwrap());
-        let mut s: Vec<u8> = vec![];
-        use std::io::Read;
-        stream.read_to_end(&mut s).unwrap();
+        let mut mixed = datagrams[0].clone();
+        mixed.destination = SocketAddr::new(IpAddr::V6(Ipv6Addr::LOCALHOST), DESTINATION_PORT);
+        assert!(pcap::write(&mut vec![], &[mixed]).is_err());
+    }
 
-        bencher.iter(|| {
-            test::black_box(serde_json::from_slice::<Vec<Packet>>(&s).unwrap());
-        })
+    #[test]
+    fn test_datagram_clamps_size() {
+        assert_eq!(datagram(&[9; 100], PACKET_DATA_SIZE, payload::SYNTHETIC_SOURCE).payload, [9; 100]);
     }
 
-    #[bench]
-    fn bench_deserialize_from_serde_bytes_normal(bencher: &mut test::Bencher) {
-        use std::io::Write;
-        let mut input_packets: Vec<_> = std::iter::repeat(Packet::default()).take(512).collect();
-        let mut stream = std::io::BufWriter::new(std::fs::OpenOptions::new().append(true).create(true).open("./out").unwrap());
-        test::black_box(serde_json::to_writer(&mut stream, &input_packets).unwrap());
-        stream.flush();
+    #[test]
+    fn test_checksums() {
+        let mut capture = vec![];
+        let datagrams = [
+            datagram(&[1, 2, 3], 3, payload::SYNTHETIC_SOURCE),
+            datagram(&[1, 2, 3], 3, SocketAddr::new(IpAddr::V6(Ipv6Addr::LOCALHOST), 9000)),
+        ];
+        pcap::write(&mut capture, &datagrams).unwrap();
 
-        let mut stream = std::io::BufReader::new(std::fs::File::open("./out").unwrap());
-        let mut s: Vec<u8> = vec![];
-        use std::io::Read;
-        stream.read_to_end(&mut s).unwrap();
+        let sum = |bytes: &[u8]| -> u32 {
+            let mut sum = bytes.chunks(2).map(|chunk| u32::from(chunk[0]) << 8 | u32::from(*chunk.get(1).unwrap_or(&0))).sum::<u32>();
+            while sum > 0xffff {
+                sum = (sum & 0xffff) + (sum >> 16);
+            }
+            sum
+        };
+        // file header, record header, AF_INET, then the IPv4 header
+        let ipv4 = &capture[24 + 16 + 4..][..20];
+        assert_eq!(sum(ipv4), 0xffff);
+        // the second record: AF_INET6, IPv6 header, then UDP; pseudo header + UDP sums to all ones
+        let frame = &capture[24 + 16 + 4 + 20 + 11 + 16..];
+        let ipv6 = &frame[4..44];
+        let udp = &frame[44..];
+        let mut pseudo = ipv6[8..40].to_vec();
+        pseudo.extend_from_slice(&[0, 0, 0, udp.len() as u8, 0, 0, 0, 17]);
+        pseudo.extend_from_slice(udp);
+        assert_eq!(sum(&pseudo), 0xffff);
+    }
 
-        bencher.iter(|| {
-            let mut reader = &s[..];
-            serde_json::from_reader::<_, Vec<Packet>>(&mut reader).unwrap();
-        })
+    #[test]
+    fn test_decode_bincode() {
+        let packets = payload::packets::<PACKET_DATA_SIZE, _>(4, |buffer| Packet {
+            buffer: SizedBuffer::from_slice(&buffer).unwrap(),
+            flags: 3,
+        });
+        let datagrams = decode(&bincode::serialize(&packets).unwrap()).unwrap();
+        assert_eq!(datagrams.len(), 4);
+        assert!(datagrams.iter().zip(&packets).all(|(datagram, packet)| datagram.payload == *packet.buffer));
     }
 }
 
 fn main() {
-    println!("Hello, world!");
+    let args: Vec<String> = std::env::args().collect();
+    match args.get(1).map(String::as_str) {
+        None => println!("Hello, world!"),
+        #[cfg(feature = "format-bincode")]
+        Some("export-pcap") => {
+            if let Err(err) = export_pcap::run(&args[2..]) {
+                eprintln!("export-pcap: {}", err);
+                std::process::exit(1);
+            }
+        }
+        Some(other) => {
+            #[cfg(feature = "format-bincode")]
+            eprintln!("unknown subcommand: {} (try export-pcap)", other);
+            #[cfg(not(feature = "format-bincode"))]
+            eprintln!("unknown subcommand: {}", other);
+            std::process::exit(2);
+        }
+    }
 }